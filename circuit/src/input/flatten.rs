@@ -1,6 +1,14 @@
 use anyhow::Result;
 use axiom_codec::HiLo;
-use serde::{Deserialize, Serialize};
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{GateInstructions, RangeChip, RangeInstructions},
+        AssignedValue, Context,
+        QuantumCell::Constant,
+    },
+    Field,
+};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 
 use crate::{impl_input_flatten_for_fixed_array, impl_input_flatten_for_tuple};
 
@@ -10,7 +18,7 @@ pub trait InputFlatten<T: Copy>: Sized {
     fn unflatten(vec: Vec<T>) -> Result<Self>;
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FixLenVec<T: Copy + Default, const N: usize>(pub Vec<T>);
 
 impl<T: Copy + Default, const N: usize> Default for FixLenVec<T, N> {
@@ -32,9 +40,59 @@ impl<T: Copy + Default, const N: usize> FixLenVec<T, N> {
     }
 }
 
-impl<T: Copy + Default, const N: usize> From<Vec<T>> for FixLenVec<T, N> {
-    fn from(vec: Vec<T>) -> Self {
-        Self(vec)
+impl<T: Copy + Default, const N: usize> TryFrom<Vec<T>> for FixLenVec<T, N> {
+    type Error = anyhow::Error;
+    fn try_from(vec: Vec<T>) -> Result<Self> {
+        Self::new(vec)
+    }
+}
+
+impl<'de, T: Copy + Default + Deserialize<'de>, const N: usize> Deserialize<'de>
+    for FixLenVec<T, N>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(Vec::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// A vector of at most `MAX` elements
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct VarLenVec<T, const MAX: usize>(Vec<T>);
+
+impl<T, const MAX: usize> Default for VarLenVec<T, MAX> {
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+
+impl<T, const MAX: usize> VarLenVec<T, MAX> {
+    pub fn new(vec: Vec<T>) -> Result<Self> {
+        if vec.len() > MAX {
+            anyhow::bail!("Input length {} exceeds max length {MAX}", vec.len());
+        }
+        Ok(VarLenVec(vec))
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T, const MAX: usize> TryFrom<Vec<T>> for VarLenVec<T, MAX> {
+    type Error = anyhow::Error;
+    fn try_from(vec: Vec<T>) -> Result<Self> {
+        Self::new(vec)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, const MAX: usize> Deserialize<'de> for VarLenVec<T, MAX> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(Vec::deserialize(deserializer)?).map_err(D::Error::custom)
     }
 }
 
@@ -72,6 +130,148 @@ impl<T: Copy> InputFlatten<T> for HiLo<T> {
     }
 }
 
+/// A signed integer, as its two's complement in the bit width of its type and its sign bit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SignedInt<T: Copy> {
+    /// The two's complement of the integer, e.g. `2^64 + x` for a negative `x: i64`
+    pub value: T,
+    /// 1 if the integer is negative, 0 otherwise
+    pub sign: T,
+}
+
+impl<T: Copy> InputFlatten<T> for SignedInt<T> {
+    const NUM_FE: usize = 2;
+    fn flatten_vec(&self) -> Vec<T> {
+        vec![self.value, self.sign]
+    }
+    fn unflatten(vec: Vec<T>) -> Result<Self> {
+        check_input_length!(vec);
+        Ok(SignedInt {
+            value: vec[0],
+            sign: vec[1],
+        })
+    }
+}
+
+/// The elements of a [VarLenVec], padded to `MAX` elements, and its length
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VarLenVecInput<E, T: Copy, const MAX: usize> {
+    pub values: [E; MAX],
+    /// The number of elements before the padding
+    pub len: T,
+}
+
+impl<E: InputFlatten<T>, T: Copy, const MAX: usize> InputFlatten<T> for VarLenVecInput<E, T, MAX> {
+    const NUM_FE: usize = E::NUM_FE * MAX + 1;
+    fn flatten_vec(&self) -> Vec<T> {
+        let mut flattened: Vec<T> = self.values.iter().flat_map(|x| x.flatten_vec()).collect();
+        flattened.push(self.len);
+        flattened
+    }
+    fn unflatten(mut vec: Vec<T>) -> Result<Self> {
+        check_input_length!(vec);
+        let len = vec.pop().unwrap();
+        let values = vec
+            .chunks(E::NUM_FE)
+            .map(|x| E::unflatten(x.to_vec()))
+            .collect::<Result<Vec<_>>>()?;
+        let values = values
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("there are exactly MAX chunks"));
+        Ok(VarLenVecInput { values, len })
+    }
+}
+
+impl<E, F: Field, const MAX: usize> VarLenVecInput<E, AssignedValue<F>, MAX> {
+    /// Returns 1 for each of the `MAX` elements that is before the padding and 0 otherwise
+    ///
+    /// Constrains `len <= MAX`.
+    pub fn mask(&self, ctx: &mut Context<F>, range: &RangeChip<F>) -> Vec<AssignedValue<F>> {
+        range.check_less_than_safe(ctx, self.len, MAX as u64 + 1);
+        let bits = (usize::BITS - MAX.leading_zeros()).max(1) as usize;
+        (0..MAX)
+            .map(|i| range.is_less_than(ctx, Constant(F::from(i as u64)), self.len, bits))
+            .collect()
+    }
+
+    /// Zeroes the entries of `values` that belong to padding, where `values[i]` belongs to the
+    /// `i`th element, e.g. the result of a subquery about it
+    pub fn mask_values(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        values: &[AssignedValue<F>],
+    ) -> Vec<AssignedValue<F>> {
+        assert_eq!(values.len(), MAX, "There must be one value per element");
+        let mask = self.mask(ctx, range);
+        values
+            .iter()
+            .zip(mask)
+            .map(|(value, bit)| range.gate().mul(ctx, *value, bit))
+            .collect()
+    }
+
+    /// Returns the sum of the entries of `values` that do not belong to padding, where
+    /// `values[i]` belongs to the `i`th element
+    pub fn sum_values(
+        &self,
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        values: &[AssignedValue<F>],
+    ) -> AssignedValue<F> {
+        let masked = self.mask_values(ctx, range, values);
+        range.gate().sum(ctx, masked)
+    }
+}
+
+impl<F: Field, const MAX: usize> VarLenVecInput<AssignedValue<F>, AssignedValue<F>, MAX> {
+    /// Returns the elements with the padding zeroed
+    pub fn masked(&self, ctx: &mut Context<F>, range: &RangeChip<F>) -> Vec<AssignedValue<F>> {
+        self.mask_values(ctx, range, &self.values)
+    }
+
+    /// Returns the sum of the elements before the padding
+    pub fn sum(&self, ctx: &mut Context<F>, range: &RangeChip<F>) -> AssignedValue<F> {
+        self.sum_values(ctx, range, &self.values)
+    }
+}
+
+/// Bytes of at most `MAX` length, padded with zeros to `MAX` bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BytesInput<T: Copy, const MAX: usize> {
+    pub bytes: [T; MAX],
+    /// The number of bytes before the padding
+    pub len: T,
+}
+
+impl<T: Copy, const MAX: usize> InputFlatten<T> for BytesInput<T, MAX> {
+    const NUM_FE: usize = MAX + 1;
+    fn flatten_vec(&self) -> Vec<T> {
+        let mut flattened = self.bytes.to_vec();
+        flattened.push(self.len);
+        flattened
+    }
+    fn unflatten(mut vec: Vec<T>) -> Result<Self> {
+        check_input_length!(vec);
+        let len = vec.pop().unwrap();
+        let bytes = vec.try_into().unwrap_or_else(|_| unreachable!());
+        Ok(BytesInput { bytes, len })
+    }
+}
+
+impl<F: Field, const MAX: usize> BytesInput<AssignedValue<F>, MAX> {
+    /// Returns 1 for each of the `MAX` bytes that is before the padding and 0 otherwise
+    ///
+    /// Constrains `len <= MAX`.
+    pub fn mask(&self, ctx: &mut Context<F>, range: &RangeChip<F>) -> Vec<AssignedValue<F>> {
+        VarLenVecInput {
+            values: self.bytes,
+            len: self.len,
+        }
+        .mask(ctx, range)
+    }
+}
+
 impl_input_flatten_for_tuple!(HiLo<T>, HiLo<T>);
 
 impl<T: Copy> InputFlatten<T> for T {
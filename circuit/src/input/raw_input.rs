@@ -1,12 +1,29 @@
 use axiom_codec::{utils::native::encode_addr_to_field, HiLo};
-use axiom_query::axiom_eth::Field;
-use ethers::types::{Address, H256};
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{GateInstructions, RangeChip, RangeInstructions},
+        AssignedValue, Context,
+        QuantumCell::Constant,
+    },
+    Field,
+};
+use ethers::types::{Address, BigEndianHash, Bytes, H256, U256};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 
-use super::flatten::FixLenVec;
+use super::flatten::{BytesInput, FixLenVec, SignedInt, VarLenVec, VarLenVecInput};
 
 pub trait RawInput<F: Field> {
     type FEType<T: Copy>;
     fn convert(&self) -> Self::FEType<F>;
+    /// Constrains an assigned input to be a valid value of this type, for types whose values
+    /// are not all field elements (ex: an `Address` is at most 160 bits)
+    #[allow(unused_variables)]
+    fn constrain(
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        witness: &Self::FEType<AssignedValue<F>>,
+    ) {
+    }
 }
 
 impl<F: Field> RawInput<F> for usize {
@@ -42,6 +59,75 @@ impl<F: Field> RawInput<F> for H256 {
     fn convert(&self) -> Self::FEType<F> {
         HiLo::from(*self)
     }
+    fn constrain(ctx: &mut Context<F>, range: &RangeChip<F>, witness: &HiLo<AssignedValue<F>>) {
+        constrain_hilo(ctx, range, witness);
+    }
+}
+
+impl<F: Field> RawInput<F> for bool {
+    type FEType<T: Copy> = T;
+    fn convert(&self) -> Self::FEType<F> {
+        F::from(*self as u64)
+    }
+    fn constrain(ctx: &mut Context<F>, range: &RangeChip<F>, witness: &AssignedValue<F>) {
+        range.gate().assert_bit(ctx, *witness);
+    }
+}
+
+macro_rules! impl_raw_input_signed {
+    ($ty:ty, $unsigned:ty) => {
+        impl<F: Field> RawInput<F> for $ty {
+            type FEType<T: Copy> = SignedInt<T>;
+            fn convert(&self) -> Self::FEType<F> {
+                SignedInt {
+                    value: F::from_u128(*self as $unsigned as u128),
+                    sign: F::from(self.is_negative() as u64),
+                }
+            }
+            fn constrain(
+                ctx: &mut Context<F>,
+                range: &RangeChip<F>,
+                witness: &SignedInt<AssignedValue<F>>,
+            ) {
+                let top_bit = <$unsigned>::BITS as usize - 1;
+                range.gate().assert_bit(ctx, witness.sign);
+                // `value - sign * 2^top_bit < 2^top_bit` holds only if `value` fits in the type
+                // and `sign` is its top bit
+                let rest = range.gate().mul_add(
+                    ctx,
+                    witness.sign,
+                    Constant(-F::from_u128(1 << top_bit)),
+                    witness.value,
+                );
+                range.range_check(ctx, rest, top_bit);
+            }
+        }
+    };
+}
+
+impl_raw_input_signed!(i8, u8);
+impl_raw_input_signed!(i16, u16);
+impl_raw_input_signed!(i32, u32);
+impl_raw_input_signed!(i64, u64);
+impl_raw_input_signed!(i128, u128);
+
+impl<F: Field> RawInput<F> for U256 {
+    type FEType<T: Copy> = HiLo<T>;
+    fn convert(&self) -> Self::FEType<F> {
+        HiLo::from(H256::from_uint(self))
+    }
+    fn constrain(ctx: &mut Context<F>, range: &RangeChip<F>, witness: &HiLo<AssignedValue<F>>) {
+        constrain_hilo(ctx, range, witness);
+    }
+}
+
+fn constrain_hilo<F: Field>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    witness: &HiLo<AssignedValue<F>>,
+) {
+    range.range_check(ctx, witness.hi(), 128);
+    range.range_check(ctx, witness.lo(), 128);
 }
 
 impl<F: Field> RawInput<F> for Address {
@@ -49,6 +135,9 @@ impl<F: Field> RawInput<F> for Address {
     fn convert(&self) -> Self::FEType<F> {
         encode_addr_to_field(self)
     }
+    fn constrain(ctx: &mut Context<F>, range: &RangeChip<F>, witness: &AssignedValue<F>) {
+        range.range_check(ctx, *witness, 160);
+    }
 }
 
 impl<F: Field, const N: usize> RawInput<F> for [u8; N] {
@@ -88,9 +177,133 @@ impl<F: Field, const N: usize> RawInput<F> for FixLenVec<usize, N> {
     type FEType<T: Copy> = [T; N];
     fn convert(&self) -> Self::FEType<F> {
         let mut res = [F::ZERO; N];
-        for i in 0..N {
-            res[i] = F::from(self.0[i] as u64);
+        for (res, val) in res.iter_mut().zip(self.0.iter()) {
+            *res = F::from(*val as u64);
         }
         res
     }
 }
+
+impl<F: Field, R: RawInput<F> + Default, const MAX: usize> RawInput<F> for VarLenVec<R, MAX> {
+    type FEType<T: Copy> = VarLenVecInput<R::FEType<T>, T, MAX>;
+    fn convert(&self) -> Self::FEType<F> {
+        let padding = R::default();
+        let elements = self.as_slice();
+        VarLenVecInput {
+            values: std::array::from_fn(|i| elements.get(i).unwrap_or(&padding).convert()),
+            len: F::from(elements.len() as u64),
+        }
+    }
+    fn constrain(
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        witness: &Self::FEType<AssignedValue<F>>,
+    ) {
+        range.check_less_than_safe(ctx, witness.len, MAX as u64 + 1);
+        for value in witness.values.iter() {
+            R::constrain(ctx, range, value);
+        }
+    }
+}
+
+/// `Bytes` of at most `MAX` length
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct VarLenBytes<const MAX: usize>(Bytes);
+
+impl<const MAX: usize> VarLenBytes<MAX> {
+    pub fn new(bytes: Bytes) -> anyhow::Result<Self> {
+        if bytes.len() > MAX {
+            anyhow::bail!("Input of {} bytes exceeds max length {MAX}", bytes.len());
+        }
+        Ok(Self(bytes))
+    }
+
+    pub fn into_inner(self) -> Bytes {
+        self.0
+    }
+}
+
+impl<'de, const MAX: usize> Deserialize<'de> for VarLenBytes<MAX> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(Bytes::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl<F: Field, const MAX: usize> RawInput<F> for VarLenBytes<MAX> {
+    type FEType<T: Copy> = BytesInput<T, MAX>;
+    fn convert(&self) -> Self::FEType<F> {
+        convert_bytes(&self.0)
+    }
+    fn constrain(
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        witness: &BytesInput<AssignedValue<F>, MAX>,
+    ) {
+        constrain_bytes(ctx, range, witness);
+    }
+}
+
+/// A UTF-8 string of at most `MAX` bytes, which is input as its bytes
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct VarLenString<const MAX: usize>(String);
+
+impl<const MAX: usize> VarLenString<MAX> {
+    pub fn new(string: String) -> anyhow::Result<Self> {
+        if string.len() > MAX {
+            anyhow::bail!("Input of {} bytes exceeds max length {MAX}", string.len());
+        }
+        Ok(Self(string))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl<'de, const MAX: usize> Deserialize<'de> for VarLenString<MAX> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl<F: Field, const MAX: usize> RawInput<F> for VarLenString<MAX> {
+    type FEType<T: Copy> = BytesInput<T, MAX>;
+    fn convert(&self) -> Self::FEType<F> {
+        convert_bytes(self.0.as_bytes())
+    }
+    fn constrain(
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        witness: &BytesInput<AssignedValue<F>, MAX>,
+    ) {
+        constrain_bytes(ctx, range, witness);
+    }
+}
+
+fn convert_bytes<F: Field, const MAX: usize>(bytes: &[u8]) -> BytesInput<F, MAX> {
+    let mut res = [F::ZERO; MAX];
+    for (res, byte) in res.iter_mut().zip(bytes) {
+        *res = F::from(*byte as u64);
+    }
+    BytesInput {
+        bytes: res,
+        len: F::from(bytes.len() as u64),
+    }
+}
+
+/// Constrains `len <= MAX`, each byte to 8 bits and the padding to be zero, so that each value
+/// has a single encoding
+fn constrain_bytes<F: Field, const MAX: usize>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    witness: &BytesInput<AssignedValue<F>, MAX>,
+) {
+    let mask = witness.mask(ctx, range);
+    for (byte, bit) in witness.bytes.iter().zip(mask) {
+        range.range_check(ctx, *byte, 8);
+        let padding = range.gate().mul_not(ctx, bit, *byte);
+        range.gate().assert_is_const(ctx, &padding, &F::ZERO);
+    }
+}
@@ -9,8 +9,13 @@ pub mod aggregation;
 pub mod constants;
 pub mod input;
 pub mod macros;
+pub mod output;
+pub mod params;
+pub mod query;
 pub mod run;
 pub mod scaffold;
+pub mod solidity;
+pub mod stats;
 pub mod subquery;
 #[cfg(test)]
 pub mod tests;
@@ -0,0 +1,144 @@
+use anyhow::{bail, Result};
+use axiom_codec::HiLo;
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{GateInstructions, RangeChip, RangeInstructions},
+        AssignedValue, Context,
+    },
+    Field,
+};
+use ethers::types::{Address, BigEndianHash, H256, U256};
+use num_bigint::BigUint;
+use num_traits::One;
+
+use crate::solidity::SolidityType;
+
+/// A native type that can be returned as a compute result, in a single `bytes32`
+pub trait RawOutput<F: Field>: Sized {
+    /// The circuit type of the result
+    type Assigned: Copy;
+    /// The Solidity type the result is decoded to on-chain
+    const SOLIDITY_TYPE: SolidityType;
+    /// Constrains `value` to be a valid value of this type and returns it as a hi-lo pair
+    fn constrain(
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        value: Self::Assigned,
+    ) -> HiLo<AssignedValue<F>>;
+    /// Decodes the native value from a compute result
+    fn decode(value: H256) -> Result<Self>;
+}
+
+/// Range checks `value` to `bits` bits, which must be at most 128, and returns it as a hi-lo pair
+fn constrain_bits<F: Field>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    value: AssignedValue<F>,
+    bits: usize,
+) -> HiLo<AssignedValue<F>> {
+    range.range_check(ctx, value, bits);
+    let zero = ctx.load_zero();
+    HiLo::from_hi_lo([zero, value])
+}
+
+/// Returns `value` as a `U256` if it fits in `bits` bits
+fn decode_bits(value: H256, bits: usize, ty: &str) -> Result<U256> {
+    let value = value.into_uint();
+    if value.bits() > bits {
+        bail!("Compute result {value:#x} does not fit in {ty}");
+    }
+    Ok(value)
+}
+
+impl<F: Field> RawOutput<F> for bool {
+    type Assigned = AssignedValue<F>;
+    const SOLIDITY_TYPE: SolidityType = SolidityType::Bool;
+    fn constrain(
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        value: Self::Assigned,
+    ) -> HiLo<AssignedValue<F>> {
+        range.gate().assert_bit(ctx, value);
+        let zero = ctx.load_zero();
+        HiLo::from_hi_lo([zero, value])
+    }
+    fn decode(value: H256) -> Result<Self> {
+        Ok(!decode_bits(value, 1, "bool")?.is_zero())
+    }
+}
+
+macro_rules! impl_raw_output_uint {
+    ($ty:ty, $bits:expr) => {
+        impl<F: Field> RawOutput<F> for $ty {
+            type Assigned = AssignedValue<F>;
+            const SOLIDITY_TYPE: SolidityType = SolidityType::Uint($bits);
+            fn constrain(
+                ctx: &mut Context<F>,
+                range: &RangeChip<F>,
+                value: Self::Assigned,
+            ) -> HiLo<AssignedValue<F>> {
+                constrain_bits(ctx, range, value, $bits)
+            }
+            fn decode(value: H256) -> Result<Self> {
+                Ok(decode_bits(value, $bits, stringify!($ty))?.as_u128() as $ty)
+            }
+        }
+    };
+}
+
+impl_raw_output_uint!(u8, 8);
+impl_raw_output_uint!(u16, 16);
+impl_raw_output_uint!(u32, 32);
+impl_raw_output_uint!(u64, 64);
+impl_raw_output_uint!(u128, 128);
+
+impl<F: Field> RawOutput<F> for Address {
+    type Assigned = AssignedValue<F>;
+    const SOLIDITY_TYPE: SolidityType = SolidityType::Address;
+    fn constrain(
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        value: Self::Assigned,
+    ) -> HiLo<AssignedValue<F>> {
+        // the hi part of an address is only 32 bits, so split it off to keep lo at 128 bits
+        range.range_check(ctx, value, 160);
+        let (hi, lo) = range.div_mod(ctx, value, BigUint::one() << 128, 160);
+        HiLo::from_hi_lo([hi, lo])
+    }
+    fn decode(value: H256) -> Result<Self> {
+        decode_bits(value, 160, "address")?;
+        Ok(Address::from(value))
+    }
+}
+
+impl<F: Field> RawOutput<F> for U256 {
+    type Assigned = HiLo<AssignedValue<F>>;
+    const SOLIDITY_TYPE: SolidityType = SolidityType::Uint(256);
+    fn constrain(
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        value: Self::Assigned,
+    ) -> HiLo<AssignedValue<F>> {
+        range.range_check(ctx, value.hi(), 128);
+        range.range_check(ctx, value.lo(), 128);
+        value
+    }
+    fn decode(value: H256) -> Result<Self> {
+        Ok(value.into_uint())
+    }
+}
+
+impl<F: Field> RawOutput<F> for H256 {
+    type Assigned = HiLo<AssignedValue<F>>;
+    const SOLIDITY_TYPE: SolidityType = SolidityType::Bytes32;
+    fn constrain(
+        ctx: &mut Context<F>,
+        range: &RangeChip<F>,
+        value: Self::Assigned,
+    ) -> HiLo<AssignedValue<F>> {
+        <U256 as RawOutput<F>>::constrain(ctx, range, value)
+    }
+    fn decode(value: H256) -> Result<Self> {
+        Ok(value)
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use anyhow::{bail, Result};
+use axiom_query::axiom_eth::{
+    halo2_proofs::plonk::ConstraintSystem,
+    halo2curves::bn256::Fr,
+    utils::keccak::decorator::RlcKeccakCircuitParams,
+    zkevm_hashes::keccak::vanilla::{KeccakCircuitConfig, KeccakConfigParams},
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::types::AxiomCircuitParams;
+
+/// What [crate::scaffold::AxiomCircuit::search_params] picks the degree by
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParamsObjective {
+    /// The smallest feasible degree
+    #[default]
+    MinK,
+    /// The feasible degree with the smallest estimated proving cost
+    MinCost,
+}
+
+impl FromStr for ParamsObjective {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "min-k" => Ok(Self::MinK),
+            "min-cost" => Ok(Self::MinCost),
+            _ => bail!("Invalid params objective: {s} (expected min-k or min-cost)"),
+        }
+    }
+}
+
+impl Display for ParamsObjective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MinK => write!(f, "min-k"),
+            Self::MinCost => write!(f, "min-cost"),
+        }
+    }
+}
+
+/// The params fitted to the circuit at one degree
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamsCandidate {
+    pub k: usize,
+    /// The params fitted with a witness generation pass, which is skipped if the instances alone
+    /// do not fit
+    pub params: Option<AxiomCircuitParams>,
+    /// Why the circuit does not fit in this degree, if it does not
+    pub infeasible: Option<String>,
+}
+
+impl ParamsCandidate {
+    /// The number of advice, lookup advice, fixed and RLC columns, and the columns of the keccak
+    /// sub-circuit, if any
+    pub fn num_columns(&self) -> Option<usize> {
+        let params = RlcKeccakCircuitParams::from(self.params.clone()?);
+        let base = &params.rlc.base;
+        Some(
+            base.num_advice_per_phase.iter().sum::<usize>()
+                + base.num_lookup_advice_per_phase.iter().sum::<usize>()
+                + base.num_fixed
+                + params.rlc.num_rlc_columns
+                + num_keccak_columns(self.k, params.keccak_rows_per_round),
+        )
+    }
+
+    /// The estimated proving cost, as the number of cells of the columns: `num_columns * 2^k`
+    pub fn cost(&self) -> Option<usize> {
+        Some(self.num_columns()? << self.k)
+    }
+
+    pub fn is_feasible(&self) -> bool {
+        self.infeasible.is_none()
+    }
+}
+
+/// Returns the number of advice and fixed columns of the keccak sub-circuit with
+/// `rows_per_round` rows per round, as [KeccakCircuitConfig] configures them. Fewer rows per round
+/// take more columns, so this changes with the degree.
+fn num_keccak_columns(k: usize, rows_per_round: usize) -> usize {
+    if rows_per_round == 0 {
+        return 0;
+    }
+    let mut meta = ConstraintSystem::<Fr>::default();
+    KeccakCircuitConfig::new(
+        &mut meta,
+        KeccakConfigParams {
+            k: k as u32,
+            rows_per_round,
+        },
+    );
+    meta.num_advice_columns() + meta.num_fixed_columns()
+}
+
+/// The candidates of a degree search, and the one picked
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamsSearch {
+    pub objective: ParamsObjective,
+    pub candidates: Vec<ParamsCandidate>,
+    /// The index of the picked candidate, if any is feasible
+    pub best: Option<usize>,
+}
+
+impl ParamsSearch {
+    pub fn new(objective: ParamsObjective, candidates: Vec<ParamsCandidate>) -> Self {
+        let feasible = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| candidate.is_feasible());
+        let best = match objective {
+            ParamsObjective::MinK => feasible.min_by_key(|(_, candidate)| candidate.k),
+            ParamsObjective::MinCost => {
+                feasible.min_by_key(|(_, candidate)| (candidate.cost(), candidate.k))
+            }
+        }
+        .map(|(i, _)| i);
+        Self {
+            objective,
+            candidates,
+            best,
+        }
+    }
+
+    pub fn best(&self) -> Option<&ParamsCandidate> {
+        self.best.map(|i| &self.candidates[i])
+    }
+}
+
+/// A table of the candidates, with the picked one marked with `*`
+impl Display for ParamsSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>3} {:>6} {:>8} {:>8} {:>6} {:>4} {:>6} {:>8} {:>12}  notes",
+            "", "k", "advice", "lookup", "fixed", "rlc", "keccak", "columns", "cost"
+        )?;
+        let join = |columns: &[usize]| columns.iter().map(|c| c.to_string()).join("/");
+        for (i, candidate) in self.candidates.iter().enumerate() {
+            let marker = if self.best == Some(i) { "*" } else { "" };
+            let notes = candidate.infeasible.as_deref().unwrap_or("");
+            let row = match candidate.params.clone() {
+                Some(params) => {
+                    let params = RlcKeccakCircuitParams::from(params);
+                    let base = &params.rlc.base;
+                    format!(
+                        "{marker:>3} {:>6} {:>8} {:>8} {:>6} {:>4} {:>6} {:>8} {:>12}  {notes}",
+                        candidate.k,
+                        join(&base.num_advice_per_phase),
+                        join(&base.num_lookup_advice_per_phase),
+                        base.num_fixed,
+                        params.rlc.num_rlc_columns,
+                        params.keccak_rows_per_round,
+                        candidate.num_columns().unwrap_or_default(),
+                        candidate.cost().unwrap_or_default(),
+                    )
+                }
+                None => format!(
+                    "{marker:>3} {:>6} {:>8} {:>8} {:>6} {:>4} {:>6} {:>8} {:>12}  {notes}",
+                    candidate.k, "-", "-", "-", "-", "-", "-", "-",
+                ),
+            };
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{bail, Result};
+use axiom_codec::types::native::AxiomV2ComputeQuery;
+use ethers::{
+    abi::{encode, Token},
+    types::{
+        transaction::eip2718::TypedTransaction, Address, Bytes, Eip1559TransactionRequest, H256,
+        U256,
+    },
+    utils::id,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{AxiomV2CircuitOutput, AxiomV2DataAndResults},
+    utils::{encode_data_query, get_data_query_hash},
+};
+
+/// Signature of `sendQuery` on the AxiomV2Query contract
+pub const SEND_QUERY_SIGNATURE: &str = "sendQuery(uint64,bytes32,(uint8,uint16,bytes32[],bytes),(address,bytes),(uint64,uint32,uint64),bytes32,address,bytes)";
+
+/// The default `maxFeePerGas` of a query (25 gwei), from the AxiomV2Query defaults
+pub const DEFAULT_MAX_FEE_PER_GAS: u64 = 25_000_000_000;
+/// The default `callbackGasLimit` of a query, from the AxiomV2Query defaults
+pub const DEFAULT_CALLBACK_GAS_LIMIT: u32 = 100_000;
+
+/// The contract called back with the query results, and extra data passed along with them
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AxiomV2Callback {
+    pub target: Address,
+    pub extra_data: Bytes,
+}
+
+/// Fees paid for fulfilling a query and calling back with its results
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AxiomV2FeeData {
+    pub max_fee_per_gas: u64,
+    pub callback_gas_limit: u32,
+    /// If non-zero, used in place of the query fee set on the contract
+    pub override_axiom_query_fee: u64,
+}
+
+impl Default for AxiomV2FeeData {
+    fn default() -> Self {
+        Self {
+            max_fee_per_gas: DEFAULT_MAX_FEE_PER_GAS,
+            callback_gas_limit: DEFAULT_CALLBACK_GAS_LIMIT,
+            override_axiom_query_fee: 0,
+        }
+    }
+}
+
+/// The arguments of `sendQuery` other than the query itself
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendQueryArgs {
+    pub source_chain_id: u64,
+    pub callback: AxiomV2Callback,
+    pub fee_data: AxiomV2FeeData,
+    pub user_salt: H256,
+    /// Receives the unused part of the payment
+    pub refundee: Address,
+}
+
+/// Returns the ABI-encoded calldata of a `sendQuery` call for the given compute query and results.
+///
+/// Fails for a compute query without a vkey, as built for circuits that can only be verified
+/// off-chain (see `build_offchain_compute_query`).
+pub fn encode_send_query_calldata(
+    compute_query: &AxiomV2ComputeQuery,
+    results: &AxiomV2DataAndResults,
+    args: &SendQueryArgs,
+) -> Result<Bytes> {
+    if compute_query.k > 0 && compute_query.vkey.is_empty() {
+        bail!("The compute query has no vkey, so the circuit can only be verified off-chain");
+    }
+    let data_query_hash = get_data_query_hash(args.source_chain_id, results);
+    let compute_query = Token::Tuple(vec![
+        Token::Uint(compute_query.k.into()),
+        Token::Uint(compute_query.result_len.into()),
+        Token::Array(
+            compute_query
+                .vkey
+                .iter()
+                .map(|fe| Token::FixedBytes(fe.as_bytes().to_vec()))
+                .collect(),
+        ),
+        Token::Bytes(compute_query.compute_proof.to_vec()),
+    ]);
+    let callback = Token::Tuple(vec![
+        Token::Address(args.callback.target),
+        Token::Bytes(args.callback.extra_data.to_vec()),
+    ]);
+    let fee_data = Token::Tuple(vec![
+        Token::Uint(args.fee_data.max_fee_per_gas.into()),
+        Token::Uint(args.fee_data.callback_gas_limit.into()),
+        Token::Uint(args.fee_data.override_axiom_query_fee.into()),
+    ]);
+    let tokens = [
+        Token::Uint(args.source_chain_id.into()),
+        Token::FixedBytes(data_query_hash.as_bytes().to_vec()),
+        compute_query,
+        callback,
+        fee_data,
+        Token::FixedBytes(args.user_salt.as_bytes().to_vec()),
+        Token::Address(args.refundee),
+        Token::Bytes(encode_data_query(args.source_chain_id, results).to_vec()),
+    ];
+    let mut calldata = id(SEND_QUERY_SIGNATURE).to_vec();
+    calldata.extend(encode(&tokens));
+    Ok(calldata.into())
+}
+
+/// Returns the ABI-encoded calldata of a `sendQuery` call for `output`
+pub fn build_send_query_calldata(
+    output: &AxiomV2CircuitOutput,
+    args: &SendQueryArgs,
+) -> Result<Bytes> {
+    encode_send_query_calldata(&output.compute_query, &output.data, args)
+}
+
+/// Returns an unsigned transaction calling the AxiomV2Query contract at `query_address` with
+/// `calldata` (see `build_send_query_calldata`), paying `payment` wei
+pub fn build_send_query_tx(
+    query_address: Address,
+    payment: U256,
+    calldata: Bytes,
+) -> TypedTransaction {
+    Eip1559TransactionRequest::new()
+        .to(query_address)
+        .value(payment)
+        .data(calldata)
+        .into()
+}
@@ -14,8 +14,8 @@ use axiom_query::axiom_eth::{
 
 use crate::{
     aggregation::create_aggregation_circuit,
-    types::{AxiomCircuitParams, AxiomV2CircuitOutput, AxiomV2DataAndResults},
-    utils::build_axiom_v2_compute_query,
+    types::{AxiomCircuitParams, AxiomInstanceLayout, AxiomV2CircuitOutput, AxiomV2DataAndResults},
+    utils::{build_axiom_v2_compute_query, build_offchain_compute_query},
 };
 
 pub fn agg_circuit_mock(agg_circuit_params: AggregationCircuitParams, snark: Snark) {
@@ -62,6 +62,7 @@ pub fn agg_circuit_run(
     pk: ProvingKey<G1Affine>,
     break_points: MultiPhaseThreadBreakPoints,
     inner_output: AxiomV2DataAndResults,
+    inner_instance_layout: AxiomInstanceLayout,
 ) -> AxiomV2CircuitOutput {
     let params = gen_srs(agg_circuit_params.degree);
     let circuit =
@@ -69,15 +70,19 @@ pub fn agg_circuit_run(
     let circuit = circuit.use_break_points(break_points);
     let agg_circuit_params = circuit.builder.config_params.clone();
     let agg_snark = gen_snark_shplonk(&params, &pk, circuit, None::<&str>);
-    let compute_query = build_axiom_v2_compute_query(
-        agg_snark.clone(),
-        AxiomCircuitParams::Base(agg_circuit_params),
-        inner_output.clone(),
-    );
-    let output = AxiomV2CircuitOutput {
+    let compute_query = if inner_instance_layout.max_public_instances > 0 {
+        log::warn!("Circuit with public instances can only be verified off-chain");
+        build_offchain_compute_query(agg_circuit_params.k, &inner_output)
+    } else {
+        build_axiom_v2_compute_query(
+            agg_snark.clone(),
+            AxiomCircuitParams::Base(agg_circuit_params),
+            inner_output.clone(),
+        )
+    };
+    AxiomV2CircuitOutput {
         compute_query,
         data: inner_output,
         snark: agg_snark,
-    };
-    output
+    }
 }
@@ -1,4 +1,4 @@
-use axiom_codec::types::native::AxiomV2ComputeQuery;
+use anyhow::Result;
 use axiom_query::axiom_eth::{
     halo2_base::utils::fs::gen_srs,
     halo2_proofs::{
@@ -9,38 +9,42 @@ use axiom_query::axiom_eth::{
     snark_verifier_sdk::{halo2::gen_snark_shplonk, Snark},
     utils::keccak::decorator::RlcKeccakCircuitParams,
 };
-use ethers::{
-    providers::{JsonRpcClient, Provider},
-    types::Bytes,
-};
 
 use crate::{
     scaffold::{AxiomCircuit, AxiomCircuitScaffold},
+    subquery::{caller::SubqueryConfig, source::SubqueryDataSource},
     types::{AxiomCircuitParams, AxiomCircuitPinning, AxiomV2CircuitOutput},
-    utils::build_axiom_v2_compute_query,
+    utils::{build_axiom_v2_compute_query, build_offchain_compute_query},
 };
 
-pub fn mock<P: JsonRpcClient + Clone, S: AxiomCircuitScaffold<P, Fr>>(
-    provider: Provider<P>,
+pub fn mock<P: SubqueryDataSource + Clone, S: AxiomCircuitScaffold<P, Fr>>(
+    data_source: P,
     raw_circuit_params: AxiomCircuitParams,
     inputs: Option<S::InputValue>,
-) {
+    subquery_config: SubqueryConfig,
+) -> Result<()> {
     let circuit_params = RlcKeccakCircuitParams::from(raw_circuit_params.clone());
     let k = circuit_params.k();
-    let mut runner = AxiomCircuit::<_, _, S>::new(provider, raw_circuit_params).use_inputs(inputs);
+    let mut runner = AxiomCircuit::<_, _, S>::new(data_source, raw_circuit_params)
+        .use_inputs(inputs)
+        .use_subquery_config(subquery_config);
     if circuit_params.keccak_rows_per_round > 0 {
         runner.calculate_params();
     }
+    runner.check_limits()?;
+    runner.check_subquery_errors()?;
     let instances = runner.instances();
     MockProver::run(k as u32, &runner, instances)
         .unwrap()
         .assert_satisfied();
+    Ok(())
 }
 
-pub fn keygen<P: JsonRpcClient + Clone, S: AxiomCircuitScaffold<P, Fr>>(
-    provider: Provider<P>,
+pub fn keygen<P: SubqueryDataSource + Clone, S: AxiomCircuitScaffold<P, Fr>>(
+    data_source: P,
     raw_circuit_params: AxiomCircuitParams,
     inputs: Option<S::InputValue>,
+    subquery_config: SubqueryConfig,
 ) -> (
     VerifyingKey<G1Affine>,
     ProvingKey<G1Affine>,
@@ -48,7 +52,9 @@ pub fn keygen<P: JsonRpcClient + Clone, S: AxiomCircuitScaffold<P, Fr>>(
 ) {
     let circuit_params = RlcKeccakCircuitParams::from(raw_circuit_params.clone());
     let params = gen_srs(circuit_params.k() as u32);
-    let mut runner = AxiomCircuit::<_, _, S>::new(provider, raw_circuit_params).use_inputs(inputs);
+    let mut runner = AxiomCircuit::<_, _, S>::new(data_source, raw_circuit_params)
+        .use_inputs(inputs)
+        .use_subquery_config(subquery_config);
     if circuit_params.keccak_rows_per_round > 0 {
         runner.calculate_params();
     }
@@ -58,59 +64,67 @@ pub fn keygen<P: JsonRpcClient + Clone, S: AxiomCircuitScaffold<P, Fr>>(
     (vk, pk, pinning)
 }
 
-pub fn prove<P: JsonRpcClient + Clone, S: AxiomCircuitScaffold<P, Fr>>(
-    provider: Provider<P>,
+pub fn prove<P: SubqueryDataSource + Clone, S: AxiomCircuitScaffold<P, Fr>>(
+    data_source: P,
     pinning: AxiomCircuitPinning,
     inputs: Option<S::InputValue>,
+    subquery_config: SubqueryConfig,
     pk: ProvingKey<G1Affine>,
-) -> Snark {
+) -> Result<Snark> {
     let circuit_params = RlcKeccakCircuitParams::from(pinning.params.clone());
     let params = gen_srs(circuit_params.k() as u32);
-    let mut runner = AxiomCircuit::<_, _, S>::prover(provider, pinning).use_inputs(inputs);
+    let mut runner = AxiomCircuit::<_, _, S>::prover(data_source, pinning)
+        .use_inputs(inputs)
+        .use_subquery_config(subquery_config);
+    runner.check_limits()?;
+    runner.check_subquery_errors()?;
     if circuit_params.keccak_rows_per_round > 0 {
         runner.calculate_params();
     }
-    gen_snark_shplonk(&params, &pk, runner, None::<&str>)
+    Ok(gen_snark_shplonk(&params, &pk, runner, None::<&str>))
 }
 
-pub fn run<P: JsonRpcClient + Clone, S: AxiomCircuitScaffold<P, Fr>>(
-    provider: Provider<P>,
+pub fn run<P: SubqueryDataSource + Clone, S: AxiomCircuitScaffold<P, Fr>>(
+    data_source: P,
     pinning: AxiomCircuitPinning,
     inputs: Option<S::InputValue>,
+    subquery_config: SubqueryConfig,
     pk: ProvingKey<G1Affine>,
-) -> AxiomV2CircuitOutput {
+) -> Result<AxiomV2CircuitOutput> {
     let circuit_params = RlcKeccakCircuitParams::from(pinning.params.clone());
     let k = circuit_params.k();
     let params = gen_srs(k as u32);
-    let mut runner = AxiomCircuit::<_, _, S>::prover(provider, pinning.clone()).use_inputs(inputs);
+    let mut runner = AxiomCircuit::<_, _, S>::prover(data_source, pinning.clone())
+        .use_inputs(inputs)
+        .use_subquery_config(subquery_config);
     let output = runner.scaffold_output();
+    runner.check_limits()?;
+    runner.check_subquery_errors()?;
     if circuit_params.keccak_rows_per_round > 0 {
         runner.calculate_params();
     }
     let snark = gen_snark_shplonk(&params, &pk, runner, None::<&str>);
     let raw_circuit_params = pinning.params.clone();
     let compute_query = match raw_circuit_params {
+        _ if pinning.instance_layout.max_public_instances > 0 => {
+            log::warn!("Circuit with public instances can only be verified off-chain");
+            build_offchain_compute_query(k, &output)
+        }
         AxiomCircuitParams::Base(_) => {
             build_axiom_v2_compute_query(snark.clone(), raw_circuit_params, output.clone())
         }
         AxiomCircuitParams::Keccak(_) => {
             log::warn!("Circuit with keccak must be aggregated before submitting on chain");
-            AxiomV2ComputeQuery {
-                k: k as u8,
-                result_len: output.compute_results.len() as u16,
-                vkey: vec![],
-                compute_proof: Bytes::default(),
-            }
+            build_offchain_compute_query(k, &output)
         }
         AxiomCircuitParams::Rlc(_) => {
             log::warn!("Circuit with RLC must be aggregated before submitting on chain");
             build_axiom_v2_compute_query(snark.clone(), raw_circuit_params, output.clone())
         }
     };
-    let output = AxiomV2CircuitOutput {
+    Ok(AxiomV2CircuitOutput {
         compute_query,
         data: output,
         snark,
-    };
-    output
+    })
 }
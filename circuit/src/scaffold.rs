@@ -1,15 +1,15 @@
 use std::{
-    borrow::BorrowMut,
     cell::RefCell,
-    fmt::Debug,
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Debug},
     mem,
-    ops::DerefMut,
+    ops::{DerefMut, RangeInclusive},
     sync::{Arc, Mutex},
 };
 
 use axiom_codec::{
     constants::{USER_MAX_OUTPUTS, USER_MAX_SUBQUERIES, USER_RESULT_FIELD_ELEMENTS},
-    types::field_elements::SUBQUERY_RESULT_LEN,
+    types::{field_elements::SUBQUERY_RESULT_LEN, native::AnySubquery},
     utils::native::decode_hilo_to_h256,
     HiLo,
 };
@@ -46,19 +46,34 @@ use axiom_query::axiom_eth::{
     },
     Field,
 };
-use ethers::providers::{JsonRpcClient, Provider};
+use ethers::types::H256;
 use itertools::Itertools;
 
 use crate::{
     input::flatten::InputFlatten,
-    subquery::caller::SubqueryCaller,
-    types::{AxiomCircuitConfig, AxiomCircuitParams, AxiomCircuitPinning, AxiomV2DataAndResults},
+    params::{ParamsCandidate, ParamsObjective, ParamsSearch},
+    stats::{count_cells, CircuitStats, RegionStats},
+    subquery::{
+        caller::{SubqueryCaller, SubqueryConfig},
+        error::{SubqueryError, SubqueryErrors},
+        source::SubqueryDataSource,
+        utils::get_subquery_type_name,
+    },
+    types::{
+        AxiomCircuitConfig, AxiomCircuitParams, AxiomCircuitPinning, AxiomInstanceLayout,
+        AxiomV2DataAndResults,
+    },
 };
 
-pub trait AxiomCircuitScaffold<P: JsonRpcClient, F: Field>: Default + Clone + Debug {
+/// The rows reserved by halo2 for blinding, determined empirically
+const UNUSABLE_ROWS: usize = 109;
+
+pub trait AxiomCircuitScaffold<P: SubqueryDataSource, F: Field>: Default + Clone + Debug {
     type InputValue: Clone + Debug + Default + InputFlatten<F>;
     type InputWitness: Clone + Debug + InputFlatten<AssignedValue<F>>;
     type FirstPhasePayload: Clone = ();
+    /// The number of values that can be exposed publicly with `SubqueryCaller::expose_public`
+    const MAX_PUBLIC_INSTANCES: usize = 0;
 
     fn virtual_assign_phase0(
         builder: &mut RlcCircuitBuilder<F>,
@@ -78,11 +93,83 @@ pub trait AxiomCircuitScaffold<P: JsonRpcClient, F: Field>: Default + Clone + De
     }
 }
 
+/// A compute function that made more subqueries or returned more outputs than the circuit has room for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LimitError {
+    Subqueries {
+        /// The number of the first subquery past the limit
+        num: usize,
+        max: usize,
+        /// The number of subqueries made of each type
+        usage: Vec<(&'static str, usize)>,
+        /// The region the subquery was made in, if any
+        region: Option<String>,
+    },
+    Outputs {
+        num: usize,
+        max: usize,
+        /// The region the first output past the limit was counted in, if any
+        region: Option<String>,
+    },
+    PublicInstances {
+        num: usize,
+        max: usize,
+        /// The region the first value past the limit was exposed in, if any
+        region: Option<String>,
+    },
+}
+
+// ` in region `name``, if the limit was exceeded in a region
+fn fmt_region(region: &Option<String>) -> String {
+    match region {
+        Some(region) => format!(" in region `{region}`"),
+        None => String::new(),
+    }
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Subqueries {
+                num,
+                max,
+                usage,
+                region,
+            } => {
+                let total: usize = usage.iter().map(|(_, count)| count).sum();
+                let usage = usage
+                    .iter()
+                    .map(|(name, count)| format!("{count} {name}"))
+                    .join(", ");
+                write!(
+                    f,
+                    "subquery #{num}{} exceeds limit of {max} ({total} subqueries made: {usage})",
+                    fmt_region(region)
+                )
+            }
+            Self::Outputs { num, max, region } => write!(
+                f,
+                "output #{}{} exceeds limit of {max} ({num} outputs returned)",
+                max + 1,
+                fmt_region(region)
+            ),
+            Self::PublicInstances { num, max, region } => write!(
+                f,
+                "public instance #{}{} exceeds limit of {max} ({num} values exposed)",
+                max + 1,
+                fmt_region(region)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LimitError {}
+
 #[derive(Clone, Debug)]
-pub struct AxiomCircuit<F: Field, P: JsonRpcClient, A: AxiomCircuitScaffold<P, F>> {
+pub struct AxiomCircuit<F: Field, P: SubqueryDataSource, A: AxiomCircuitScaffold<P, F>> {
     pub builder: RefCell<RlcCircuitBuilder<F>>,
     pub inputs: Option<A::InputValue>,
-    pub provider: Provider<P>,
+    pub data_source: P,
     range: RangeChip<F>,
     payload: RefCell<Option<A::FirstPhasePayload>>,
     output: RefCell<AxiomV2DataAndResults>,
@@ -90,47 +177,51 @@ pub struct AxiomCircuit<F: Field, P: JsonRpcClient, A: AxiomCircuitScaffold<P, F
     keccak_rows_per_round: usize,
     max_user_outputs: usize,
     max_user_subqueries: usize,
+    max_public_instances: usize,
+    subquery_config: SubqueryConfig,
+    prefetched: RefCell<Option<BTreeMap<AnySubquery, H256>>>,
+    subquery_errors: RefCell<Vec<SubqueryError>>,
+    limit_errors: RefCell<Vec<LimitError>>,
+    regions: RefCell<Vec<RegionStats>>,
 }
 
-impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCircuit<F, P, A> {
-    pub fn new(provider: Provider<P>, circuit_params: AxiomCircuitParams) -> Self {
-        Self::from_stage(provider, circuit_params, CircuitBuilderStage::Mock)
+impl<F: Field, P: SubqueryDataSource + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCircuit<F, P, A> {
+    pub fn new(data_source: P, circuit_params: AxiomCircuitParams) -> Self {
+        Self::from_stage(data_source, circuit_params, CircuitBuilderStage::Mock)
     }
 
-    pub fn prover(provider: Provider<P>, pinning: AxiomCircuitPinning) -> Self {
-        let mut circuit = Self::from_stage(provider, pinning.params, CircuitBuilderStage::Prover);
+    pub fn prover(data_source: P, pinning: AxiomCircuitPinning) -> Self {
+        let mut circuit =
+            Self::from_stage(data_source, pinning.params, CircuitBuilderStage::Prover);
         circuit.set_break_points(pinning.break_points);
+        circuit.set_instance_layout(pinning.instance_layout);
         circuit
     }
 
     pub fn from_stage(
-        provider: Provider<P>,
+        data_source: P,
         circuit_params: AxiomCircuitParams,
         stage: CircuitBuilderStage,
     ) -> Self {
         let params = RlcKeccakCircuitParams::from(circuit_params);
-        let rlc_bits = if params.rlc.num_rlc_columns > 0 {
-            DEFAULT_RLC_CACHE_BITS
-        } else {
-            0
-        };
-        let builder =
-            RlcCircuitBuilder::<F>::from_stage(stage, rlc_bits).use_params(params.rlc.clone());
-        let range = RangeChip::new(
-            params.rlc.base.lookup_bits.unwrap(),
-            builder.base.lookup_manager().clone(),
-        );
+        let (builder, range) = new_builder_and_range(stage, &params);
         Self {
             builder: RefCell::new(builder),
             range,
             inputs: None,
-            provider,
+            data_source,
             payload: RefCell::new(None),
             keccak_rows_per_round: params.keccak_rows_per_round,
             output: Default::default(),
             keccak_call_collector: RefCell::new(Default::default()),
             max_user_outputs: USER_MAX_OUTPUTS,
             max_user_subqueries: USER_MAX_SUBQUERIES,
+            max_public_instances: A::MAX_PUBLIC_INSTANCES,
+            subquery_config: Default::default(),
+            prefetched: RefCell::new(None),
+            subquery_errors: RefCell::new(Vec::new()),
+            limit_errors: RefCell::new(Vec::new()),
+            regions: RefCell::new(Vec::new()),
         }
     }
 
@@ -152,8 +243,39 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
         self
     }
 
+    pub fn set_max_public_instances(&mut self, max_public_instances: usize) {
+        self.max_public_instances = max_public_instances;
+    }
+
+    pub fn use_max_public_instances(mut self, max_public_instances: usize) -> Self {
+        self.set_max_public_instances(max_public_instances);
+        self
+    }
+
+    pub fn set_instance_layout(&mut self, instance_layout: AxiomInstanceLayout) {
+        self.max_user_outputs = instance_layout.max_user_outputs;
+        self.max_user_subqueries = instance_layout.max_user_subqueries;
+        self.max_public_instances = instance_layout.max_public_instances;
+    }
+
+    pub fn use_instance_layout(mut self, instance_layout: AxiomInstanceLayout) -> Self {
+        self.set_instance_layout(instance_layout);
+        self
+    }
+
+    pub fn set_subquery_config(&mut self, subquery_config: SubqueryConfig) {
+        self.subquery_config = subquery_config;
+        self.prefetched.replace(None);
+    }
+
+    pub fn use_subquery_config(mut self, subquery_config: SubqueryConfig) -> Self {
+        self.set_subquery_config(subquery_config);
+        self
+    }
+
     pub fn set_inputs(&mut self, inputs: Option<A::InputValue>) {
         self.inputs = inputs;
+        self.prefetched.replace(None);
     }
 
     pub fn use_inputs(mut self, inputs: Option<A::InputValue>) -> Self {
@@ -185,6 +307,7 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
     pub fn set_pinning(&mut self, pinning: AxiomCircuitPinning) {
         self.set_params(pinning.params);
         self.set_break_points(pinning.break_points);
+        self.set_instance_layout(pinning.instance_layout);
     }
 
     pub fn use_pinning(mut self, pinning: AxiomCircuitPinning) -> Self {
@@ -192,12 +315,13 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
         self
     }
 
-    pub fn set_provider(&mut self, provider: Provider<P>) {
-        self.provider = provider;
+    pub fn set_data_source(&mut self, data_source: P) {
+        self.data_source = data_source;
+        self.prefetched.replace(None);
     }
 
-    pub fn use_provider(mut self, provider: Provider<P>) -> Self {
-        self.set_provider(provider);
+    pub fn use_data_source(mut self, data_source: P) -> Self {
+        self.set_data_source(data_source);
         self
     }
 
@@ -214,10 +338,34 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
         }
     }
 
+    /// Returns the break points, if they were set by synthesis or [AxiomCircuit::set_break_points]
+    fn try_break_points(&self) -> Option<RlcThreadBreakPoints> {
+        let builder = self.builder.borrow();
+        let base_is_set = builder
+            .base
+            .core()
+            .phase_manager
+            .iter()
+            .all(|pm| pm.break_points.borrow().is_some());
+        let rlc_is_set =
+            builder.num_rlc_columns == 0 || builder.rlc_manager.break_points.borrow().is_some();
+        drop(builder);
+        (base_is_set && rlc_is_set).then(|| self.break_points())
+    }
+
     pub fn pinning(&self) -> AxiomCircuitPinning {
         AxiomCircuitPinning {
             params: self.params(),
             break_points: self.break_points(),
+            instance_layout: self.instance_layout(),
+        }
+    }
+
+    pub fn instance_layout(&self) -> AxiomInstanceLayout {
+        AxiomInstanceLayout {
+            max_user_outputs: self.max_user_outputs,
+            max_user_subqueries: self.max_user_subqueries,
+            max_public_instances: self.max_public_instances,
         }
     }
 
@@ -233,24 +381,101 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
         self.max_user_subqueries * SUBQUERY_RESULT_LEN
     }
 
+    pub fn public_num_instances(&self) -> usize {
+        self.max_public_instances
+    }
+
+    fn assign_inputs(&self, builder: &mut RlcCircuitBuilder<F>) -> A::InputWitness {
+        let flattened_inputs = self.inputs.clone().unwrap_or_default().flatten_vec();
+        let assigned_input_vec = builder.base.main(0).assign_witnesses(flattened_inputs);
+        A::InputWitness::unflatten(assigned_input_vec).unwrap()
+    }
+
+    /// Runs phase0 of the compute function on a scratch builder, with subqueries answered from
+    /// `results` (or by the mock strategy if missing), and returns every subquery it made
+    fn dry_run_subqueries(&self, results: &BTreeMap<AnySubquery, H256>) -> Vec<AnySubquery> {
+        let params = RlcKeccakCircuitParams::from(self.params());
+        let (mut builder, range) = new_builder_and_range(CircuitBuilderStage::Mock, &params);
+        let assigned_inputs = self.assign_inputs(&mut builder);
+        let subquery_caller = Arc::new(Mutex::new(
+            SubqueryCaller::new(self.data_source.clone(), true)
+                .use_prefetched(results.clone())
+                .use_mock_strategy(self.subquery_config.mock_strategy.clone()),
+        ));
+        A::virtual_assign_phase0(
+            &mut builder,
+            &range,
+            subquery_caller.clone(),
+            &mut Vec::new(),
+            assigned_inputs,
+        );
+        let subquery_caller = subquery_caller.lock().unwrap();
+        subquery_caller
+            .subqueries
+            .values()
+            .flatten()
+            .map(|(subquery, _)| subquery.clone())
+            .collect()
+    }
+
+    /// Collects the subqueries made by the compute function and fetches them with at most
+    /// `parallelism` requests in flight.
+    ///
+    /// Subqueries whose inputs depend on the results of other subqueries are discovered by
+    /// repeating the dry run until no new subqueries appear. Until their results are fetched, the
+    /// dry run answers them with placeholder values from the mock strategy, so the compute
+    /// function must not panic natively on those values. Subqueries that fail to fetch are
+    /// left out, so they are fetched (and fail) again during witness generation.
+    fn prefetch_subqueries(&self, parallelism: usize) -> BTreeMap<AnySubquery, H256> {
+        let mut results = BTreeMap::new();
+        let mut attempted = BTreeSet::new();
+        loop {
+            let missing = self
+                .dry_run_subqueries(&results)
+                .into_iter()
+                .filter(|subquery| attempted.insert(subquery.clone()))
+                .collect_vec();
+            if missing.is_empty() {
+                break;
+            }
+            log::info!("Prefetching {} subqueries", missing.len());
+            let fetched = self.data_source.fetch_many(missing.clone(), parallelism);
+            for (subquery, result) in missing.into_iter().zip(fetched) {
+                match result {
+                    Ok(value) => {
+                        results.insert(subquery, value);
+                    }
+                    Err(e) => log::warn!("Failed to prefetch subquery {subquery:?}: {e}"),
+                }
+            }
+        }
+        results
+    }
+
     fn virtual_assign_phase0(&self) {
         if self.payload.borrow().is_some() {
             return;
         }
         let is_inputs = self.inputs.is_none();
-        let flattened_inputs = self.inputs.clone().unwrap_or_default().flatten_vec();
-        let assigned_input_vec = self
-            .builder
-            .borrow_mut()
-            .base
-            .main(0)
-            .assign_witnesses(flattened_inputs);
-        let assigned_inputs = A::InputWitness::unflatten(assigned_input_vec).unwrap();
-
-        let subquery_caller = Arc::new(Mutex::new(SubqueryCaller::new(
-            self.provider.clone(),
-            is_inputs,
-        )));
+        let mut subquery_caller = SubqueryCaller::new(self.data_source.clone(), is_inputs)
+            .use_dedup(self.subquery_config.dedup)
+            .use_max_subqueries(self.max_user_subqueries)
+            .use_max_outputs(self.max_user_outputs)
+            .use_max_public_instances(self.max_public_instances)
+            .use_mock_strategy(self.subquery_config.mock_strategy.clone());
+        match self.subquery_config.prefetch_parallelism {
+            Some(parallelism) if !is_inputs => {
+                if self.prefetched.borrow().is_none() {
+                    let prefetched = self.prefetch_subqueries(parallelism);
+                    self.prefetched.replace(Some(prefetched));
+                }
+                subquery_caller.set_prefetched(self.prefetched.borrow().clone().unwrap());
+            }
+            _ => {}
+        }
+        let assigned_inputs = self.assign_inputs(&mut self.builder.borrow_mut());
+
+        let subquery_caller = Arc::new(Mutex::new(subquery_caller));
         let mut callback = Vec::new();
         let payload = A::virtual_assign_phase0(
             &mut self.builder.borrow_mut(),
@@ -260,6 +485,13 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
             assigned_inputs,
         );
         self.payload.borrow_mut().replace(payload);
+        // count the outputs that the scaffold returned without counting them itself
+        {
+            let mut caller = subquery_caller.lock().unwrap();
+            for _ in caller.num_outputs()..callback.len() {
+                caller.add_output();
+            }
+        }
 
         let mut flattened_callback = callback
             .clone()
@@ -283,8 +515,21 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
                 .load_constant(F::ZERO)
         });
 
+        let mut public_instances = subquery_caller.lock().unwrap().public_instances.clone();
+        let num_public_instances = public_instances.len();
+        public_instances.resize_with(self.public_num_instances(), || {
+            self.builder
+                .borrow_mut()
+                .base
+                .main(0)
+                .load_constant(F::ZERO)
+        });
+
         flattened_callback.extend(subquery_instances);
-        let instances = vec![flattened_callback];
+        let mut instances = vec![flattened_callback];
+        if self.max_public_instances > 0 {
+            instances.push(public_instances);
+        }
         self.builder.borrow_mut().base.assigned_instances = instances.clone();
 
         let circuit_output = callback
@@ -300,6 +545,46 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
             subquery_caller.lock().unwrap().keccak_var_len_calls.clone();
         self.keccak_call_collector.borrow_mut().fix_len_calls =
             subquery_caller.lock().unwrap().keccak_fix_len_calls.clone();
+        self.subquery_errors
+            .replace(subquery_caller.lock().unwrap().errors.clone());
+        self.regions
+            .replace(subquery_caller.lock().unwrap().regions.clone());
+
+        let mut limit_errors = Vec::new();
+        let caller = subquery_caller.lock().unwrap();
+        if let Some(overflow) = caller.overflow.clone() {
+            let usage = self
+                .output
+                .borrow()
+                .data_query
+                .iter()
+                .map(|subquery| get_subquery_type_name(&subquery.subquery_data.0))
+                .counts()
+                .into_iter()
+                .sorted()
+                .collect();
+            limit_errors.push(LimitError::Subqueries {
+                num: overflow.num,
+                max: self.max_user_subqueries,
+                usage,
+                region: overflow.region,
+            });
+        }
+        if let Some(overflow) = caller.output_overflow.clone() {
+            limit_errors.push(LimitError::Outputs {
+                num: callback.len(),
+                max: self.max_user_outputs,
+                region: overflow.region,
+            });
+        }
+        if let Some(overflow) = caller.public_overflow.clone() {
+            limit_errors.push(LimitError::PublicInstances {
+                num: num_public_instances,
+                max: self.max_public_instances,
+                region: overflow.region,
+            });
+        }
+        self.limit_errors.replace(limit_errors);
     }
 
     fn virtual_assign_phase1(&self) {
@@ -318,6 +603,7 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
         self.virtual_assign_phase0();
+        self.check_errors_for_synthesis()?;
         if !self.keccak_call_collector.borrow().fix_len_calls.is_empty()
             || !self.keccak_call_collector.borrow().var_len_calls.is_empty()
         {
@@ -338,6 +624,7 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
         config.base.initialize(&mut layouter);
         let k = self.builder.borrow().params().base.k;
         self.virtual_assign_phase0();
+        self.check_errors_for_synthesis()?;
         if let Some(keccak_config) = keccak_config {
             keccak_config.load_aux_tables(&mut layouter, k as u32)?;
             let keccak_calls = mem::take(self.keccak_call_collector.borrow_mut().deref_mut());
@@ -397,7 +684,153 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
         self.output.borrow_mut().data_query.clear();
     }
 
+    /// Configures the circuit for degree `k` from witness generation passes, keeping its circuit
+    /// type: the lookup bits are set to the width of the widest range check, capped at `k - 1`,
+    /// and the advice, lookup advice, fixed and RLC columns (and the keccak rows per round, if
+    /// keccak is used) to what the compute function needs
+    ///
+    /// Returns the number of keccak_f permutations.
+    pub fn auto_configure(&mut self, k: usize) -> usize {
+        get_usable_rows(k);
+        let mut params = RlcKeccakCircuitParams::from(self.params());
+        params.rlc.base.k = k;
+        params.rlc.base.lookup_bits = Some(self.fit_lookup_bits(&params));
+        self.reset_builder(&params);
+        self.fit_params()
+    }
+
+    /// Returns the lookup bits, at most `k - 1`, with which the range checks of the compute function
+    /// use the fewest lookup cells, preferring more bits on ties. This is the width of the widest
+    /// range check, if it is less than `k - 1`.
+    ///
+    /// Narrower lookups are tried from `k - 1` down, until they use more lookup cells than `k - 1`.
+    fn fit_lookup_bits(&mut self, params: &RlcKeccakCircuitParams) -> usize {
+        let k = params.rlc.base.k;
+        let mut params = params.clone();
+        params.rlc.base.lookup_bits = Some(k - 1);
+        let max_lookup_cells = self.count_lookup_cells(&params);
+        let (mut best_bits, mut best_lookup_cells) = (k - 1, max_lookup_cells);
+        // without range checks, the lookup bits do not matter
+        if max_lookup_cells == 0 {
+            return best_bits;
+        }
+        for bits in (1..k - 1).rev() {
+            params.rlc.base.lookup_bits = Some(bits);
+            let lookup_cells = self.count_lookup_cells(&params);
+            if lookup_cells > max_lookup_cells {
+                break;
+            }
+            if lookup_cells < best_lookup_cells {
+                (best_bits, best_lookup_cells) = (bits, lookup_cells);
+            }
+        }
+        best_bits
+    }
+
+    /// Returns the lookup cells used by a witness generation pass with `params`
+    fn count_lookup_cells(&mut self, params: &RlcKeccakCircuitParams) -> usize {
+        self.reset_builder(params);
+        self.virtual_assign_with_mock_keccak();
+        let (_, lookup_cells) = count_cells(&self.builder.borrow());
+        self.clear();
+        lookup_cells
+    }
+
+    /// Replaces the builder and range chip with empty ones for `params`
+    fn reset_builder(&mut self, params: &RlcKeccakCircuitParams) {
+        let (builder, range) = new_builder_and_range(CircuitBuilderStage::Mock, params);
+        self.builder.replace(builder);
+        self.range = range;
+        self.keccak_rows_per_round = params.keccak_rows_per_round;
+        self.clear();
+    }
+
+    /// Fits the circuit to each degree in `ks` with [AxiomCircuit::auto_configure], and picks the
+    /// feasible degree that is best for `objective`. The circuit keeps its original params.
+    pub fn search_params(
+        &mut self,
+        ks: RangeInclusive<usize>,
+        objective: ParamsObjective,
+    ) -> ParamsSearch {
+        let num_instances = self
+            .instance_layout()
+            .num_instances()
+            .into_iter()
+            .max()
+            .unwrap();
+        let (placeholder, _) = new_builder_and_range(
+            CircuitBuilderStage::Mock,
+            &RlcKeccakCircuitParams::from(self.params()),
+        );
+        let builder = self.builder.replace(placeholder);
+        let range = self.range.clone();
+        let keccak_rows_per_round = self.keccak_rows_per_round;
+        let candidates = ks
+            .map(|k| {
+                let usable_rows = (1usize << k).saturating_sub(UNUSABLE_ROWS);
+                // checked before fitting, which needs at least one usable row and k > 0
+                if usable_rows == 0 {
+                    return ParamsCandidate {
+                        k,
+                        params: None,
+                        infeasible: Some(format!("all {} rows are reserved", 1usize << k)),
+                    };
+                }
+                if num_instances > usable_rows {
+                    return ParamsCandidate {
+                        k,
+                        params: None,
+                        infeasible: Some(format!(
+                            "{num_instances} instances exceed {usable_rows} usable rows"
+                        )),
+                    };
+                }
+                let capacity = self.auto_configure(k);
+                let infeasible = (capacity > 0 && self.keccak_rows_per_round == 0)
+                    .then(|| format!("too few rows for {capacity} keccak_f permutations"));
+                ParamsCandidate {
+                    k,
+                    params: Some(self.params()),
+                    infeasible,
+                }
+            })
+            .collect();
+        self.builder.replace(builder);
+        self.range = range;
+        self.keccak_rows_per_round = keccak_rows_per_round;
+        ParamsSearch::new(objective, candidates)
+    }
+
     pub fn calculate_params(&mut self) {
+        self.fit_params();
+    }
+
+    /// Calculates the params from a witness generation pass and returns the number of keccak_f
+    /// permutations
+    fn fit_params(&mut self) -> usize {
+        let k = self.builder.borrow().base.config_params.k;
+        let usable_rows = get_usable_rows(k);
+        let capacity = self.virtual_assign_with_mock_keccak();
+
+        self.builder
+            .borrow_mut()
+            .calculate_params(Some(UNUSABLE_ROWS));
+        // This is the inverse of [zkevm_hashes::keccak::vanilla::keccak_packed_multi::get_keccak_capacity].
+        let rows_per_round = usable_rows / (capacity * (NUM_ROUNDS + 1) + 1 + NUM_WORDS_TO_ABSORB);
+        // log::info!("RlcKeccakCircuit used capacity: {capacity}");
+        // log::info!("RlcKeccakCircuit optimal rows_per_round : {rows_per_round}");
+        // Empirically more than 50 rows makes the rotations inhibit performance.
+        if capacity > 0 || self.keccak_rows_per_round > 0 {
+            self.keccak_rows_per_round = rows_per_round.min(50);
+        }
+
+        self.clear();
+        capacity
+    }
+
+    /// Runs witness generation of both phases, packing the keccak calls into mock keccak_f
+    /// permutations, and returns the number of keccak_f permutations
+    fn virtual_assign_with_mock_keccak(&self) -> usize {
         self.virtual_assign_phase0();
         let keccak_calls = mem::take(self.keccak_call_collector.borrow_mut().deref_mut());
         let mut capacity = 0;
@@ -425,24 +858,45 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
         keccak_calls.pack_and_constrain(
             virtual_keccak_fs,
             self.builder.borrow_mut().base.pool(0),
-            self.range.borrow_mut(),
+            &self.range,
         );
         self.virtual_assign_phase1();
+        capacity
+    }
 
-        // TMP: use empirical constants
-        let unusable_rows = 109;
-        self.builder
-            .borrow_mut()
-            .calculate_params(Some(unusable_rows));
-        let usable_rows = (1 << self.builder.borrow().base.config_params.k) - unusable_rows;
-        // This is the inverse of [zkevm_hashes::keccak::vanilla::keccak_packed_multi::get_keccak_capacity].
-        let rows_per_round = usable_rows / (capacity * (NUM_ROUNDS + 1) + 1 + NUM_WORDS_TO_ABSORB);
-        // log::info!("RlcKeccakCircuit used capacity: {capacity}");
-        // log::info!("RlcKeccakCircuit optimal rows_per_round : {rows_per_round}");
-        // Empirically more than 50 rows makes the rotations inhibit performance.
-        self.keccak_rows_per_round = rows_per_round.min(50);
-
+    /// Returns the cells, constraints, keccak_f permutations, subqueries and outputs used by a
+    /// witness generation pass of both phases, and the cells used by each named region
+    pub fn stats(&self) -> CircuitStats {
+        self.clear();
+        let num_keccak_f = self.virtual_assign_with_mock_keccak();
+        let builder = self.builder.borrow();
+        let statistics = builder.statistics();
+        let copy_manager = builder.copy_manager().lock().unwrap();
+        let output = self.output.borrow();
+        let stats = CircuitStats {
+            advice_cells_per_phase: statistics.base.gate.total_advice_per_phase,
+            lookup_cells_per_phase: statistics.base.total_lookup_advice_per_phase,
+            fixed_constants: statistics.base.gate.total_fixed,
+            copy_constraints: copy_manager.advice_equalities.len()
+                + copy_manager.constant_equalities.len(),
+            rlc_cells: statistics.total_rlc_advice,
+            num_keccak_f,
+            subqueries: output
+                .data_query
+                .iter()
+                .map(|subquery| get_subquery_type_name(&subquery.subquery_data.0))
+                .counts()
+                .into_iter()
+                .sorted()
+                .collect(),
+            num_outputs: output.compute_results.len(),
+            regions: self.regions.borrow().clone(),
+        };
+        drop(copy_manager);
+        drop(builder);
+        drop(output);
         self.clear();
+        stats
     }
 
     pub fn instances(&self) -> Vec<Vec<F>> {
@@ -460,21 +914,106 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> AxiomCir
         self.virtual_assign_phase0();
         self.output.borrow().clone()
     }
+
+    /// Returns the subqueries that could not be fetched during witness generation
+    pub fn subquery_errors(&self) -> Vec<SubqueryError> {
+        self.virtual_assign_phase0();
+        self.subquery_errors.borrow().clone()
+    }
+
+    pub fn check_subquery_errors(&self) -> Result<(), SubqueryErrors> {
+        let errors = self.subquery_errors();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SubqueryErrors(errors))
+        }
+    }
+
+    /// Returns the ways in which the compute function exceeded the configured maximum number of
+    /// subqueries or outputs
+    pub fn limit_errors(&self) -> Vec<LimitError> {
+        self.virtual_assign_phase0();
+        self.limit_errors.borrow().clone()
+    }
+
+    pub fn check_limits(&self) -> Result<(), LimitError> {
+        match self.limit_errors().into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    fn check_errors_for_synthesis(&self) -> Result<(), Error> {
+        for err in self.limit_errors.borrow().iter() {
+            log::error!("{err}");
+        }
+        for err in self.subquery_errors.borrow().iter() {
+            log::error!("{err}");
+        }
+        if self.subquery_errors.borrow().is_empty() && self.limit_errors.borrow().is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Synthesis)
+        }
+    }
+}
+
+/// Returns the rows of a circuit of degree `k` that are not reserved, panicking if there are none
+fn get_usable_rows(k: usize) -> usize {
+    let usable_rows = (1usize << k).saturating_sub(UNUSABLE_ROWS);
+    assert!(
+        usable_rows > 0,
+        "Degree {k} leaves no usable rows, as {UNUSABLE_ROWS} rows are reserved"
+    );
+    usable_rows
+}
+
+fn new_builder_and_range<F: Field>(
+    stage: CircuitBuilderStage,
+    params: &RlcKeccakCircuitParams,
+) -> (RlcCircuitBuilder<F>, RangeChip<F>) {
+    let rlc_bits = if params.rlc.num_rlc_columns > 0 {
+        DEFAULT_RLC_CACHE_BITS
+    } else {
+        0
+    };
+    let builder =
+        RlcCircuitBuilder::<F>::from_stage(stage, rlc_bits).use_params(params.rlc.clone());
+    let range = RangeChip::new(
+        params.rlc.base.lookup_bits.unwrap(),
+        builder.base.lookup_manager().clone(),
+    );
+    (builder, range)
 }
 
-impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> Circuit<F>
+impl<F: Field, P: SubqueryDataSource + Clone, A: AxiomCircuitScaffold<P, F>> Circuit<F>
     for AxiomCircuit<F, P, A>
 {
     type Config = AxiomCircuitConfig<F>;
     type Params = AxiomCircuitParams;
     type FloorPlanner = SimpleFloorPlanner;
 
+    /// Returns a circuit with the same params, break points (if set), instance layout and
+    /// subquery config, built for keygen and run without inputs, so subqueries are mocked
     fn without_witnesses(&self) -> Self {
-        unimplemented!()
+        let mut circuit = Self::from_stage(
+            self.data_source.clone(),
+            self.params(),
+            CircuitBuilderStage::Keygen,
+        )
+        .use_instance_layout(self.instance_layout())
+        .use_subquery_config(self.subquery_config.clone());
+        if let Some(break_points) = self.try_break_points() {
+            circuit.set_break_points(break_points);
+        }
+        circuit
     }
 
     fn params(&self) -> Self::Params {
-        let rlc_params = self.builder.borrow().params();
+        let mut rlc_params = self.builder.borrow().params();
+        // public values are exposed in a second instance column
+        rlc_params.base.num_instance_columns = self.instance_layout().num_instance_columns();
         if rlc_params.num_rlc_columns == 0 && self.keccak_rows_per_round == 0 {
             AxiomCircuitParams::Base(rlc_params.base)
         } else if self.keccak_rows_per_round == 0 {
@@ -522,11 +1061,11 @@ impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F>> Circuit<
     }
 }
 
-impl<F: Field, P: JsonRpcClient + Clone, A: AxiomCircuitScaffold<P, F> + Default> CircuitExt<F>
+impl<F: Field, P: SubqueryDataSource + Clone, A: AxiomCircuitScaffold<P, F> + Default> CircuitExt<F>
     for AxiomCircuit<F, P, A>
 {
     fn num_instance(&self) -> Vec<usize> {
-        vec![self.output_num_instances() + self.subquery_num_instances()]
+        self.instance_layout().num_instances()
     }
 
     fn instances(&self) -> Vec<Vec<F>> {
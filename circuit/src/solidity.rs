@@ -0,0 +1,201 @@
+use std::{collections::HashSet, fmt::Write};
+
+use anyhow::{bail, Result};
+use ethers::types::H256;
+use serde::{Deserialize, Serialize};
+
+/// The Solidity type a compute result is decoded to from its `bytes32`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolidityType {
+    Bytes32,
+    /// `uint<bits>`, where `bits` is a multiple of 8 up to 256
+    Uint(u16),
+    Address,
+    Bool,
+}
+
+impl SolidityType {
+    fn name(&self) -> String {
+        match self {
+            Self::Bytes32 => "bytes32".to_string(),
+            Self::Uint(bits) => format!("uint{bits}"),
+            Self::Address => "address".to_string(),
+            Self::Bool => "bool".to_string(),
+        }
+    }
+
+    /// Returns the Solidity expression decoding `value`, a `bytes32`, to this type
+    fn decode(&self, value: &str) -> String {
+        match self {
+            Self::Bytes32 => value.to_string(),
+            Self::Uint(256) => format!("uint256({value})"),
+            Self::Uint(bits) => format!("uint{bits}(uint256({value}))"),
+            Self::Address => format!("address(uint160(uint256({value})))"),
+            Self::Bool => format!("{value} != bytes32(0)"),
+        }
+    }
+}
+
+/// A named compute result, in the order it is returned from the compute function
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolidityResult {
+    pub name: String,
+    pub ty: SolidityType,
+}
+
+impl SolidityResult {
+    pub fn new(name: impl Into<String>, ty: SolidityType) -> Self {
+        Self {
+            name: name.into(),
+            ty,
+        }
+    }
+}
+
+/// Generates a Solidity `AxiomV2Client` skeleton whose `_axiomV2Callback` decodes `axiomResults`
+/// into named, typed locals
+#[derive(Clone, Debug)]
+pub struct SolidityCallbackStub {
+    contract_name: String,
+    query_schema: H256,
+    results: Vec<SolidityResult>,
+}
+
+impl SolidityCallbackStub {
+    pub fn new(contract_name: impl Into<String>, query_schema: H256) -> Self {
+        Self {
+            contract_name: contract_name.into(),
+            query_schema,
+            results: vec![],
+        }
+    }
+
+    pub fn set_results(&mut self, results: Vec<SolidityResult>) {
+        self.results = results;
+    }
+
+    pub fn use_results(mut self, results: Vec<SolidityResult>) -> Self {
+        self.set_results(results);
+        self
+    }
+
+    pub fn generate(&self) -> Result<String> {
+        check_identifier(&self.contract_name)?;
+        let mut names = HashSet::new();
+        for result in &self.results {
+            check_identifier(&result.name)?;
+            if !names.insert(&result.name) {
+                bail!("Duplicate result name `{}`", result.name);
+            }
+            if let SolidityType::Uint(bits) = result.ty {
+                if bits == 0 || bits > 256 || bits % 8 != 0 {
+                    bail!(
+                        "Invalid Solidity type uint{bits} for result `{}`",
+                        result.name
+                    );
+                }
+            }
+        }
+
+        let mut decoded = String::new();
+        for (i, result) in self.results.iter().enumerate() {
+            writeln!(
+                decoded,
+                "        {} {} = {};",
+                result.ty.name(),
+                result.name,
+                result.ty.decode(&format!("axiomResults[{i}]"))
+            )?;
+        }
+
+        Ok(format!(
+            r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+import {{ AxiomV2Client }} from "@axiom-crypto/v2-periphery/client/AxiomV2Client.sol";
+
+contract {name} is AxiomV2Client {{
+    /// @dev The query schema of the compute circuit
+    bytes32 public constant QUERY_SCHEMA = {query_schema:?};
+
+    /// @dev The chain ID queries are made on
+    uint64 public immutable SOURCE_CHAIN_ID;
+
+    constructor(address axiomV2QueryAddress, uint64 sourceChainId) AxiomV2Client(axiomV2QueryAddress) {{
+        SOURCE_CHAIN_ID = sourceChainId;
+    }}
+
+    function _validateAxiomV2Call(
+        AxiomCallbackType, /* callbackType */
+        uint64 sourceChainId,
+        address, /* caller */
+        bytes32 querySchema,
+        uint256, /* queryId */
+        bytes calldata /* extraData */
+    ) internal view override {{
+        require(sourceChainId == SOURCE_CHAIN_ID, "Source chain ID does not match");
+        require(querySchema == QUERY_SCHEMA, "Invalid query schema");
+    }}
+
+    function _axiomV2Callback(
+        uint64, /* sourceChainId */
+        address, /* caller */
+        bytes32, /* querySchema */
+        uint256, /* queryId */
+        bytes32[] calldata axiomResults,
+        bytes calldata /* extraData */
+    ) internal override {{
+        require(axiomResults.length == {num_results}, "Unexpected number of results");
+{decoded}
+        // TODO: use the results
+    }}
+}}
+"#,
+            name = self.contract_name,
+            query_schema = self.query_schema,
+            num_results = self.results.len(),
+        ))
+    }
+}
+
+// keywords and reserved words of Solidity, which cannot be used as identifiers
+const SOLIDITY_RESERVED_WORDS: &str = "\
+    abstract address after alias anonymous apply as assembly auto bool break byte calldata case \
+    catch constant constructor continue contract copyof days default define delete do else emit \
+    enum ether event external fallback false final for function gwei hex hours if immutable \
+    implements import in indexed inline interface internal is let library macro mapping match \
+    memory minutes modifier mutable new null of override partial payable pragma private promise \
+    public pure receive reference relocatable return returns sealed seconds sizeof static storage \
+    string struct super supports switch this throw true try type typedef typeof unchecked unicode \
+    using var view virtual weeks wei while years";
+
+// whether `name` is a sized elementary type, e.g. `uint64`, `bytes32` or `fixed128x18`
+fn is_elementary_type(name: &str) -> bool {
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    ["uint", "int", "bytes"]
+        .iter()
+        .any(|prefix| name.strip_prefix(prefix).is_some_and(is_digits))
+        || ["ufixed", "fixed"].iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|size| size.chars().all(|c| c.is_ascii_digit() || c == 'x'))
+        })
+}
+
+fn check_identifier(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if !valid {
+        bail!("`{name}` is not a valid Solidity identifier");
+    }
+    if SOLIDITY_RESERVED_WORDS
+        .split_whitespace()
+        .any(|word| word == name)
+        || is_elementary_type(name)
+    {
+        bail!("`{name}` is a reserved word in Solidity");
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use std::fmt::{self, Display};
+
+use axiom_query::axiom_eth::{rlc::circuit::builder::RlcCircuitBuilder, Field};
+use itertools::Itertools;
+use serde::Serialize;
+
+/// The cells used by the calls of a named region of a compute function, see
+/// [crate::subquery::caller::SubqueryCaller::enter_region]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionStats {
+    /// The names of the region and the regions it is nested in, joined with `/`
+    pub name: String,
+    pub calls: usize,
+    /// Advice cells of all phases, including RLC cells
+    pub advice_cells: usize,
+    pub lookup_cells: usize,
+}
+
+/// The cost of a compute function, from a witness generation pass of both phases
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitStats {
+    /// Advice cells of the basic gate, per phase
+    pub advice_cells_per_phase: Vec<usize>,
+    /// Advice cells that are range checked with the lookup table, per phase
+    pub lookup_cells_per_phase: Vec<usize>,
+    /// Distinct constants, each taking a fixed cell
+    pub fixed_constants: usize,
+    /// Equality constraints between advice cells, and between advice cells and constants
+    pub copy_constraints: usize,
+    pub rlc_cells: usize,
+    pub num_keccak_f: usize,
+    /// The number of subqueries made of each type
+    pub subqueries: Vec<(&'static str, usize)>,
+    pub num_outputs: usize,
+    /// The named regions, in the order they were first entered
+    pub regions: Vec<RegionStats>,
+}
+
+/// Returns the advice cells of all phases, including RLC cells, and the lookup cells assigned so far
+pub fn count_cells<F: Field>(builder: &RlcCircuitBuilder<F>) -> (usize, usize) {
+    let statistics = builder.statistics();
+    let advice_cells = statistics
+        .base
+        .gate
+        .total_advice_per_phase
+        .iter()
+        .sum::<usize>()
+        + statistics.total_rlc_advice;
+    let lookup_cells = statistics.base.total_lookup_advice_per_phase.iter().sum();
+    (advice_cells, lookup_cells)
+}
+
+impl Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let per_phase = |cells: &[usize]| {
+            cells
+                .iter()
+                .enumerate()
+                .map(|(phase, cells)| format!("{cells} (phase {phase})"))
+                .join(", ")
+        };
+        let num_subqueries: usize = self.subqueries.iter().map(|(_, count)| count).sum();
+        let subqueries = self
+            .subqueries
+            .iter()
+            .map(|(name, count)| format!("{count} {name}"))
+            .join(", ");
+        writeln!(
+            f,
+            "advice cells:     {}",
+            per_phase(&self.advice_cells_per_phase)
+        )?;
+        writeln!(
+            f,
+            "lookup cells:     {}",
+            per_phase(&self.lookup_cells_per_phase)
+        )?;
+        writeln!(f, "fixed constants:  {}", self.fixed_constants)?;
+        writeln!(f, "copy constraints: {}", self.copy_constraints)?;
+        writeln!(f, "RLC cells:        {}", self.rlc_cells)?;
+        writeln!(f, "keccak_f:         {}", self.num_keccak_f)?;
+        if num_subqueries == 0 {
+            writeln!(f, "subqueries:       0")?;
+        } else {
+            writeln!(f, "subqueries:       {num_subqueries} ({subqueries})")?;
+        }
+        write!(f, "outputs:          {}", self.num_outputs)?;
+        if self.regions.is_empty() {
+            return Ok(());
+        }
+        let width = self
+            .regions
+            .iter()
+            .map(|region| region.name.len())
+            .max()
+            .unwrap_or_default()
+            .max("region".len());
+        write!(
+            f,
+            "\n\n{:<width$} {:>6} {:>12} {:>12}",
+            "region", "calls", "advice", "lookup"
+        )?;
+        for region in self.regions.iter() {
+            write!(
+                f,
+                "\n{:<width$} {:>6} {:>12} {:>12}",
+                region.name, region.calls, region.advice_cells, region.lookup_cells
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,15 +1,18 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use axiom_codec::types::native::{AccountSubquery, AnySubquery};
 use axiom_query::axiom_eth::{halo2_base::AssignedValue, Field};
 use ethers::{
     providers::{JsonRpcClient, Middleware, Provider},
-    types::{BigEndianHash, BlockId, H256, U256},
+    types::{BigEndianHash, BlockId, EIP1186ProofResponse, H256},
+    utils::keccak256,
 };
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
-use tokio::runtime::Runtime;
 
-use super::{caller::FetchSubquery, types::AssignedAccountSubquery};
+use super::{
+    caller::FetchSubquery, error::InvalidSubqueryReason, source::SubqueryDataSource,
+    types::AssignedAccountSubquery,
+};
 use crate::impl_fr_from;
 
 #[derive(FromPrimitive, Copy, Clone)]
@@ -21,60 +24,50 @@ pub enum AccountField {
 }
 impl_fr_from!(AccountField);
 
+/// The root of an empty storage trie
+const EMPTY_STORAGE_HASH: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
 pub async fn get_account_field_value<P: JsonRpcClient>(
     provider: &Provider<P>,
     query: AccountSubquery,
 ) -> Result<H256> {
     let block_id = Some(BlockId::from(query.block_number as u64));
 
-    let account_field = AccountField::from_u32(query.field_idx).expect("Invalid field index");
+    let account_field = AccountField::from_u32(query.field_idx)
+        .ok_or(InvalidSubqueryReason::InvalidFieldIdx(query.field_idx))?;
+    let proof = provider
+        .get_proof(query.addr, vec![], block_id)
+        .await
+        .context("fetching account proof")?;
+    if is_empty_account(&proof) {
+        return Ok(get_nonexistent_account_field_value(account_field));
+    }
     let val = match account_field {
-        AccountField::Nonce => {
-            let nonce = provider.get_transaction_count(query.addr, block_id).await;
-            if nonce.is_err() {
-                return Ok(get_nonexistent_account_field_value(
-                    query.field_idx as usize,
-                ));
-            }
-            H256::from_uint(&nonce.unwrap())
-        }
-        AccountField::Balance => {
-            let balance = provider.get_balance(query.addr, block_id).await;
-            if balance.is_err() {
-                return Ok(get_nonexistent_account_field_value(
-                    query.field_idx as usize,
-                ));
-            }
-            H256::from_uint(&balance.unwrap())
-        }
-        AccountField::StorageHash => {
-            let proof = provider.get_proof(query.addr, vec![], block_id).await;
-            if proof.is_err() {
-                return Ok(get_nonexistent_account_field_value(
-                    query.field_idx as usize,
-                ));
-            }
-            proof.unwrap().storage_hash
-        }
-        AccountField::CodeHash => {
-            let proof = provider.get_proof(query.addr, vec![], block_id).await;
-            if proof.is_err() {
-                return Ok(get_nonexistent_account_field_value(
-                    query.field_idx as usize,
-                ));
-            }
-            proof.unwrap().code_hash
-        }
+        AccountField::Nonce => H256::from_uint(&proof.nonce),
+        AccountField::Balance => H256::from_uint(&proof.balance),
+        AccountField::StorageHash => proof.storage_hash,
+        AccountField::CodeHash => proof.code_hash,
     };
 
     Ok(val)
 }
 
+// Empty accounts are removed from the state since EIP-161, so a proof of an empty account shows
+// that the account does not exist. Clients return either zero or the empty hashes for the
+// storage and code hash of an account that does not exist.
+fn is_empty_account(proof: &EIP1186ProofResponse) -> bool {
+    proof.nonce.is_zero()
+        && proof.balance.is_zero()
+        && (proof.code_hash.is_zero() || proof.code_hash == H256(keccak256(b"")))
+        && (proof.storage_hash.is_zero() || proof.storage_hash == EMPTY_STORAGE_HASH)
+}
+
 impl<F: Field> FetchSubquery<F> for AssignedAccountSubquery<F> {
-    fn fetch<P: JsonRpcClient>(&self, p: &Provider<P>) -> Result<H256> {
-        let rt = Runtime::new()?;
-        let res = rt.block_on(get_account_field_value(p, (*self).into()))?;
-        Ok(res)
+    fn fetch<P: SubqueryDataSource>(&self, p: &P) -> Result<H256> {
+        p.get_account_field_value((*self).into())
     }
 
     fn any_subquery(&self) -> AnySubquery {
@@ -86,17 +79,11 @@ impl<F: Field> FetchSubquery<F> for AssignedAccountSubquery<F> {
     }
 }
 
-fn get_nonexistent_account_field_value(field_idx: usize) -> H256 {
-    let account_field = AccountField::from_usize(field_idx).expect("Invalid field index");
+pub(crate) fn get_nonexistent_account_field_value(account_field: AccountField) -> H256 {
     match account_field {
-        AccountField::Nonce => H256::from_uint(&U256::from(0)),
-        AccountField::Balance => H256::from_uint(&U256::from(0)),
-        AccountField::StorageHash => H256::from_uint(
-            &U256::from_dec_str(
-                "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
-            )
-            .unwrap(),
-        ),
-        AccountField::CodeHash => H256::from_uint(&U256::from(0)),
+        AccountField::Nonce => H256::zero(),
+        AccountField::Balance => H256::zero(),
+        AccountField::StorageHash => EMPTY_STORAGE_HASH,
+        AccountField::CodeHash => H256::zero(),
     }
 }
@@ -0,0 +1,207 @@
+use std::{
+    fmt::{self, Display},
+    fs::{self, File},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use anyhow::{bail, Result};
+use axiom_codec::types::native::{
+    AccountSubquery, AnySubquery, HeaderSubquery, ReceiptSubquery, SolidityNestedMappingSubquery,
+    StorageSubquery, Subquery, TxSubquery,
+};
+use ethers::types::H256;
+use log::warn;
+
+use super::source::{SubqueryDataSource, SubqueryFixtureEntry};
+
+/// How a [CachedDataSource] uses its on-disk cache
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SubqueryCacheMode {
+    /// Read cached results and cache any results that are missing
+    #[default]
+    Use,
+    /// Fetch every result from the inner data source and overwrite the cached result
+    Refresh,
+    /// Neither read from nor write to the cache
+    Bypass,
+}
+
+impl FromStr for SubqueryCacheMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "use" => Ok(Self::Use),
+            "refresh" => Ok(Self::Refresh),
+            "bypass" => Ok(Self::Bypass),
+            _ => bail!("Invalid subquery cache mode: {s} (expected use, refresh or bypass)"),
+        }
+    }
+}
+
+impl Display for SubqueryCacheMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Use => write!(f, "use"),
+            Self::Refresh => write!(f, "refresh"),
+            Self::Bypass => write!(f, "bypass"),
+        }
+    }
+}
+
+/// A [SubqueryDataSource] that caches the results of another data source on disk
+///
+/// Each result is stored as a [SubqueryFixtureEntry] in `<dir>/<key>.json`, where `key` is the
+/// keccak hash of the encoded subquery. Results are not keyed by chain, so a cache directory
+/// should only ever be used for a single source chain.
+#[derive(Clone, Debug)]
+pub struct CachedDataSource<S: SubqueryDataSource> {
+    inner: S,
+    dir: PathBuf,
+    mode: SubqueryCacheMode,
+}
+
+impl<S: SubqueryDataSource> CachedDataSource<S> {
+    pub fn new(inner: S, dir: impl Into<PathBuf>, mode: SubqueryCacheMode) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn mode(&self) -> SubqueryCacheMode {
+        self.mode
+    }
+
+    /// Returns the path of the cache file for `subquery`
+    pub fn cache_path(&self, subquery: &AnySubquery) -> PathBuf {
+        let key = Subquery::from(subquery.clone()).keccak();
+        self.dir.join(format!("{key:?}.json"))
+    }
+
+    fn read(&self, subquery: &AnySubquery) -> Option<H256> {
+        let path = self.cache_path(subquery);
+        if !path.exists() {
+            return None;
+        }
+        let entry: SubqueryFixtureEntry = match File::open(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|f| serde_json::from_reader(f).map_err(anyhow::Error::from))
+        {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("Ignoring unreadable subquery cache entry {path:?}: {e}");
+                return None;
+            }
+        };
+        if entry.subquery != *subquery {
+            warn!("Ignoring subquery cache entry {path:?} for a different subquery");
+            return None;
+        }
+        Some(entry.value)
+    }
+
+    fn write(&self, subquery: AnySubquery, value: H256) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.cache_path(&subquery);
+        // write to a temporary file first so an interrupted write never leaves a partial entry
+        let tmp_path = path.with_extension("json.tmp");
+        let f = File::create(&tmp_path)?;
+        serde_json::to_writer(f, &SubqueryFixtureEntry { subquery, value })?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    fn get_or_fetch(
+        &self,
+        subquery: AnySubquery,
+        fetch: impl FnOnce(&S) -> Result<H256>,
+    ) -> Result<H256> {
+        if self.mode == SubqueryCacheMode::Use {
+            if let Some(value) = self.read(&subquery) {
+                return Ok(value);
+            }
+        }
+        let value = fetch(&self.inner)?;
+        if self.mode != SubqueryCacheMode::Bypass {
+            if let Err(e) = self.write(subquery, value) {
+                warn!("Failed to write subquery cache entry: {e}");
+            }
+        }
+        Ok(value)
+    }
+}
+
+impl<S: SubqueryDataSource> SubqueryDataSource for CachedDataSource<S> {
+    fn get_header_field_value(&self, query: HeaderSubquery) -> Result<H256> {
+        self.get_or_fetch(AnySubquery::Header(query.clone()), |s| {
+            s.get_header_field_value(query)
+        })
+    }
+
+    fn get_account_field_value(&self, query: AccountSubquery) -> Result<H256> {
+        self.get_or_fetch(AnySubquery::Account(query.clone()), |s| {
+            s.get_account_field_value(query)
+        })
+    }
+
+    fn get_storage_field_value(&self, query: StorageSubquery) -> Result<H256> {
+        self.get_or_fetch(AnySubquery::Storage(query.clone()), |s| {
+            s.get_storage_field_value(query)
+        })
+    }
+
+    fn get_tx_field_value(&self, query: TxSubquery) -> Result<H256> {
+        self.get_or_fetch(AnySubquery::Transaction(query.clone()), |s| {
+            s.get_tx_field_value(query)
+        })
+    }
+
+    fn get_receipt_field_value(&self, query: ReceiptSubquery) -> Result<H256> {
+        self.get_or_fetch(AnySubquery::Receipt(query.clone()), |s| {
+            s.get_receipt_field_value(query)
+        })
+    }
+
+    fn get_solidity_nested_mapping_field_value(
+        &self,
+        query: SolidityNestedMappingSubquery,
+    ) -> Result<H256> {
+        self.get_or_fetch(AnySubquery::SolidityNestedMapping(query.clone()), |s| {
+            s.get_solidity_nested_mapping_field_value(query)
+        })
+    }
+
+    fn fetch_many(&self, subqueries: Vec<AnySubquery>, parallelism: usize) -> Vec<Result<H256>> {
+        let mut results: Vec<Option<Result<H256>>> = subqueries
+            .iter()
+            .map(|subquery| match self.mode {
+                SubqueryCacheMode::Use => self.read(subquery).map(Ok),
+                _ => None,
+            })
+            .collect();
+        let (missing_idxs, missing): (Vec<_>, Vec<_>) = subqueries
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| results[*i].is_none())
+            .unzip();
+        let fetched = self.inner.fetch_many(missing.clone(), parallelism);
+        for ((i, subquery), result) in missing_idxs.into_iter().zip(missing).zip(fetched) {
+            if let Ok(value) = &result {
+                if self.mode != SubqueryCacheMode::Bypass {
+                    if let Err(e) = self.write(subquery, *value) {
+                        warn!("Failed to write subquery cache entry: {e}");
+                    }
+                }
+            }
+            results[i] = Some(result);
+        }
+        results.into_iter().map(Option::unwrap).collect()
+    }
+}
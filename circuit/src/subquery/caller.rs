@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt};
 
 use anyhow::Result;
 use axiom_codec::{
@@ -9,26 +9,30 @@ use axiom_codec::{
 use axiom_query::axiom_eth::{
     halo2_base::{AssignedValue, Context, ContextTag},
     keccak::promise::{KeccakFixLenCall, KeccakVarLenCall},
+    rlc::circuit::builder::RlcCircuitBuilder,
     utils::encode_h256_to_hilo,
     Field,
 };
-use ethers::{
-    providers::{JsonRpcClient, Provider},
-    types::H256,
-};
+use ethers::types::H256;
 use itertools::Itertools;
 
 use super::{
+    error::SubqueryError,
     keccak::{KeccakSubquery, KeccakSubqueryTypes},
+    mock::MockStrategy,
+    source::SubqueryDataSource,
     types::Subquery,
 };
-use crate::subquery::{types::RawSubquery, utils::get_subquery_type_from_any_subquery};
+use crate::{
+    stats::{count_cells, RegionStats},
+    subquery::utils::get_subquery_type_from_any_subquery,
+};
 
 pub trait FetchSubquery<F: Field>: Clone {
     fn flatten(&self) -> Vec<AssignedValue<F>>;
-    fn fetch<P: JsonRpcClient>(&self, p: &Provider<P>) -> Result<H256>;
+    fn fetch<P: SubqueryDataSource>(&self, p: &P) -> Result<H256>;
     fn any_subquery(&self) -> AnySubquery;
-    fn call<P: JsonRpcClient>(
+    fn call<P: SubqueryDataSource>(
         &self,
         ctx: &mut Context<F>,
         caller: &mut SubqueryCaller<P, F>,
@@ -37,33 +41,176 @@ pub trait FetchSubquery<F: Field>: Clone {
     }
 }
 
-pub struct SubqueryCaller<P: JsonRpcClient, F: Field> {
-    pub provider: Provider<P>,
+/// Options that control how subqueries are answered during witness generation
+#[derive(Clone, Debug, Default)]
+pub struct SubqueryConfig {
+    /// If set, the subqueries of a compute function are collected in a dry run and fetched up
+    /// front, with at most this many requests in flight. The dry run answers subqueries that are
+    /// not fetched yet with `mock_strategy`, so the compute function runs on placeholder values.
+    pub prefetch_parallelism: Option<usize>,
+    /// If true, a subquery that was already made is answered with the earlier result instead of
+    /// being added to the data query again, and its inputs are constrained to equal the inputs of
+    /// the earlier call. Which calls are merged depends on the subquery values, so keys must be
+    /// generated with inputs that repeat the same subqueries as the inputs being proven.
+    pub dedup: bool,
+    /// How subqueries are answered when the circuit is run without inputs, e.g. during keygen
+    pub mock_strategy: MockStrategy,
+}
+
+/// The first call made past one of the limits of the circuit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LimitOverflow {
+    /// The number of the call, counting from 1
+    pub num: usize,
+    /// The region the call was made in, if any
+    pub region: Option<String>,
+}
+
+impl fmt::Display for LimitOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.num)?;
+        if let Some(region) = &self.region {
+            write!(f, " in region `{region}`")?;
+        }
+        Ok(())
+    }
+}
+
+// the flattened inputs and result of the first call of a subquery
+type DedupCall<F> = (Vec<AssignedValue<F>>, HiLo<AssignedValue<F>>);
+
+pub struct SubqueryCaller<P: SubqueryDataSource, F: Field> {
+    pub data_source: P,
     pub subqueries: BTreeMap<ContextTag, Vec<(AnySubquery, H256)>>,
     pub subquery_assigned_values: BTreeMap<ContextTag, Vec<AssignedValue<F>>>,
     pub keccak_fix_len_calls: Vec<(KeccakFixLenCall<F>, HiLo<AssignedValue<F>>)>,
     pub keccak_var_len_calls: Vec<(KeccakVarLenCall<F>, HiLo<AssignedValue<F>>)>,
-    // if true, the fetched subquery will always be H256::zero()
+    // values exposed with `expose_public`, in the second instance column
+    pub public_instances: Vec<AssignedValue<F>>,
+    // the cells used by the named regions of the compute function
+    pub regions: Vec<RegionStats>,
+    // the regions entered and not yet exited, with the cells counted when they were entered
+    region_stack: Vec<(usize, (usize, usize))>,
+    // results that were fetched ahead of time, these are used instead of the data source
+    pub prefetched: BTreeMap<AnySubquery, H256>,
+    // subqueries that could not be fetched, their results are assigned as zero
+    pub errors: Vec<SubqueryError>,
+    // if true, repeated subqueries reuse the inputs and result of their first call
+    dedup: bool,
+    dedup_calls: BTreeMap<AnySubquery, DedupCall<F>>,
+    // if set, the first subquery made past this limit is recorded in `overflow`
+    max_subqueries: Option<usize>,
+    num_subqueries: usize,
+    pub overflow: Option<LimitOverflow>,
+    // if set, the first output past this limit is recorded in `output_overflow`
+    max_outputs: Option<usize>,
+    num_outputs: usize,
+    pub output_overflow: Option<LimitOverflow>,
+    // if set, the first value exposed past this limit is recorded in `public_overflow`
+    max_public_instances: Option<usize>,
+    pub public_overflow: Option<LimitOverflow>,
+    // if true, the fetched subquery is always given by `mock_strategy`
     mock_subquery_call: bool,
+    mock_strategy: MockStrategy,
 }
 
-impl<P: JsonRpcClient, F: Field> SubqueryCaller<P, F> {
-    pub fn new(provider: Provider<P>, mock: bool) -> Self {
+impl<P: SubqueryDataSource, F: Field> SubqueryCaller<P, F> {
+    pub fn new(data_source: P, mock: bool) -> Self {
         Self {
-            provider,
+            data_source,
             subqueries: BTreeMap::new(),
             subquery_assigned_values: BTreeMap::new(),
             keccak_fix_len_calls: Vec::new(),
             keccak_var_len_calls: Vec::new(),
+            public_instances: Vec::new(),
+            regions: Vec::new(),
+            region_stack: Vec::new(),
+            prefetched: BTreeMap::new(),
+            errors: Vec::new(),
+            dedup: false,
+            dedup_calls: BTreeMap::new(),
+            max_subqueries: None,
+            num_subqueries: 0,
+            overflow: None,
+            max_outputs: None,
+            num_outputs: 0,
+            output_overflow: None,
+            max_public_instances: None,
+            public_overflow: None,
             mock_subquery_call: mock,
+            mock_strategy: MockStrategy::Zero,
         }
     }
 
+    pub fn set_prefetched(&mut self, prefetched: BTreeMap<AnySubquery, H256>) {
+        self.prefetched = prefetched;
+    }
+
+    pub fn use_prefetched(mut self, prefetched: BTreeMap<AnySubquery, H256>) -> Self {
+        self.set_prefetched(prefetched);
+        self
+    }
+
+    pub fn set_dedup(&mut self, dedup: bool) {
+        self.dedup = dedup;
+    }
+
+    pub fn use_dedup(mut self, dedup: bool) -> Self {
+        self.set_dedup(dedup);
+        self
+    }
+
+    pub fn set_mock_strategy(&mut self, mock_strategy: MockStrategy) {
+        self.mock_strategy = mock_strategy;
+    }
+
+    pub fn use_mock_strategy(mut self, mock_strategy: MockStrategy) -> Self {
+        self.set_mock_strategy(mock_strategy);
+        self
+    }
+
+    pub fn set_max_subqueries(&mut self, max_subqueries: usize) {
+        self.max_subqueries = Some(max_subqueries);
+    }
+
+    pub fn use_max_subqueries(mut self, max_subqueries: usize) -> Self {
+        self.set_max_subqueries(max_subqueries);
+        self
+    }
+
+    pub fn set_max_outputs(&mut self, max_outputs: usize) {
+        self.max_outputs = Some(max_outputs);
+    }
+
+    pub fn use_max_outputs(mut self, max_outputs: usize) -> Self {
+        self.set_max_outputs(max_outputs);
+        self
+    }
+
+    pub fn set_max_public_instances(&mut self, max_public_instances: usize) {
+        self.max_public_instances = Some(max_public_instances);
+    }
+
+    pub fn use_max_public_instances(mut self, max_public_instances: usize) -> Self {
+        self.set_max_public_instances(max_public_instances);
+        self
+    }
+
     pub fn clear(&mut self) {
         self.subqueries.clear();
         self.subquery_assigned_values.clear();
         self.keccak_fix_len_calls.clear();
         self.keccak_var_len_calls.clear();
+        self.errors.clear();
+        self.public_instances.clear();
+        self.dedup_calls.clear();
+        self.num_subqueries = 0;
+        self.overflow = None;
+        self.num_outputs = 0;
+        self.output_overflow = None;
+        self.public_overflow = None;
+        self.regions.clear();
+        self.region_stack.clear();
     }
 
     pub fn data_query(&self) -> Vec<Subquery> {
@@ -72,17 +219,89 @@ impl<P: JsonRpcClient, F: Field> SubqueryCaller<P, F> {
             .values()
             .flat_map(|val| {
                 val.iter()
-                    .map(|(any_subquery, result)| Subquery {
-                        subquery_type: get_subquery_type_from_any_subquery(&any_subquery.clone()),
-                        subquery_data: RawSubquery(any_subquery.clone()),
-                        val: *result,
-                    })
+                    .map(|(any_subquery, result)| Subquery::new(any_subquery.clone(), *result))
                     .collect_vec()
             })
             .collect_vec();
         subqueries
     }
 
+    /// Exposes `value` as a public instance of the circuit
+    pub fn expose_public(&mut self, value: AssignedValue<F>) {
+        self.public_instances.push(value);
+        let num = self.public_instances.len();
+        match self.max_public_instances {
+            Some(max) if num > max && self.public_overflow.is_none() => {
+                let overflow = self.get_overflow(num);
+                log::error!("public instance {overflow} exceeds limit of {max}");
+                self.public_overflow = Some(overflow);
+            }
+            _ => {}
+        }
+    }
+
+    /// Counts an output of the compute function, which are returned in order
+    pub fn add_output(&mut self) {
+        self.num_outputs += 1;
+        match self.max_outputs {
+            Some(max) if self.num_outputs > max && self.output_overflow.is_none() => {
+                let overflow = self.get_overflow(self.num_outputs);
+                log::error!("output {overflow} exceeds limit of {max}");
+                self.output_overflow = Some(overflow);
+            }
+            _ => {}
+        }
+    }
+
+    /// The number of outputs counted with [SubqueryCaller::add_output]
+    pub fn num_outputs(&self) -> usize {
+        self.num_outputs
+    }
+
+    // the call `num` made in the innermost region that is entered, if any
+    fn get_overflow(&self, num: usize) -> LimitOverflow {
+        LimitOverflow {
+            num,
+            region: self
+                .region_stack
+                .last()
+                .map(|(index, _)| self.regions[*index].name.clone()),
+        }
+    }
+
+    /// Starts a named region, nested in the region entered last, if any. The cells assigned in
+    /// `builder` until [SubqueryCaller::exit_region] are attributed to it.
+    pub fn enter_region(&mut self, name: &str, builder: &RlcCircuitBuilder<F>) {
+        let name = match self.region_stack.last() {
+            Some((parent, _)) => format!("{}/{name}", self.regions[*parent].name),
+            None => name.to_string(),
+        };
+        let index = match self.regions.iter().position(|region| region.name == name) {
+            Some(index) => index,
+            None => {
+                self.regions.push(RegionStats {
+                    name,
+                    ..Default::default()
+                });
+                self.regions.len() - 1
+            }
+        };
+        self.region_stack.push((index, count_cells(builder)));
+    }
+
+    /// Ends the region entered last
+    pub fn exit_region(&mut self, builder: &RlcCircuitBuilder<F>) {
+        let (index, (advice_start, lookup_start)) = self
+            .region_stack
+            .pop()
+            .expect("exit_region called without a matching enter_region");
+        let (advice_end, lookup_end) = count_cells(builder);
+        let region = &mut self.regions[index];
+        region.calls += 1;
+        region.advice_cells += advice_end - advice_start;
+        region.lookup_cells += lookup_end - lookup_start;
+    }
+
     pub fn instances(&self) -> Vec<AssignedValue<F>> {
         self.subquery_assigned_values
             .values()
@@ -96,12 +315,37 @@ impl<P: JsonRpcClient, F: Field> SubqueryCaller<P, F> {
         ctx: &mut Context<F>,
         subquery: T,
     ) -> HiLo<AssignedValue<F>> {
-        let result = if self.mock_subquery_call {
-            H256::zero()
+        let any_subquery = subquery.any_subquery();
+        if self.dedup {
+            if let Some((inputs, hilo)) = self.dedup_calls.get(&any_subquery) {
+                for (prev, input) in inputs.iter().zip(subquery.flatten()) {
+                    if prev.cell != input.cell {
+                        ctx.constrain_equal(prev, &input);
+                    }
+                }
+                return *hilo;
+            }
+        }
+        self.num_subqueries += 1;
+        match self.max_subqueries {
+            Some(max) if self.num_subqueries > max && self.overflow.is_none() => {
+                let overflow = self.get_overflow(self.num_subqueries);
+                log::error!("subquery {overflow} exceeds limit of {max}");
+                self.overflow = Some(overflow);
+            }
+            _ => {}
+        }
+        let result = if let Some(result) = self.prefetched.get(&any_subquery) {
+            *result
+        } else if self.mock_subquery_call {
+            self.mock_strategy.mock_value(&any_subquery)
         } else {
-            subquery.fetch(&self.provider).unwrap()
+            subquery.fetch(&self.data_source).unwrap_or_else(|e| {
+                self.errors
+                    .push(SubqueryError::new(any_subquery.clone(), e));
+                H256::zero()
+            })
         };
-        let any_subquery = subquery.any_subquery();
         let val = (any_subquery.clone(), result);
         self.subqueries
             .entry(ctx.tag())
@@ -123,7 +367,12 @@ impl<P: JsonRpcClient, F: Field> SubqueryCaller<P, F> {
             .entry(ctx.tag())
             .and_modify(|thread| thread.extend(flattened_subquery.clone()))
             .or_insert(flattened_subquery);
-        HiLo::from_hi_lo([hi, lo])
+        let hilo = HiLo::from_hi_lo([hi, lo]);
+        if self.dedup {
+            self.dedup_calls
+                .insert(any_subquery, (subquery.flatten(), hilo));
+        }
+        hilo
     }
 
     pub fn keccak<T: KeccakSubquery<F>>(
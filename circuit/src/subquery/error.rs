@@ -0,0 +1,145 @@
+use std::fmt;
+
+use axiom_codec::types::native::AnySubquery;
+
+/// Why a subquery cannot be answered from the chain data it refers to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvalidSubqueryReason {
+    InvalidFieldIdx(u32),
+    BlockNotFound,
+    TxNotFound,
+    ReceiptNotFound,
+    LogNotFound,
+    EventSchemaMismatch,
+    TopicNotFound,
+    DataNotFound,
+    InvalidCalldata,
+    InvalidCalldataIdx,
+    InvalidContractDataIdx,
+    GasPriceNotAvailable,
+    UseLogIdx,
+    InvalidMappingDepth(u8),
+    FieldNotAvailable(&'static str),
+    UnsupportedTxType(u64),
+    TxDataTooLong { len: usize, max: usize },
+    AccessListTooLong { len: usize, max: usize },
+    TooManyLogs { num: usize, max: usize },
+    LogDataTooLong { len: usize, max: usize },
+}
+
+impl fmt::Display for InvalidSubqueryReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFieldIdx(idx) => write!(f, "invalid field index {idx}"),
+            Self::BlockNotFound => write!(f, "block does not exist"),
+            Self::TxNotFound => write!(f, "transaction does not exist"),
+            Self::ReceiptNotFound => write!(f, "receipt does not exist"),
+            Self::LogNotFound => write!(f, "log does not exist"),
+            Self::EventSchemaMismatch => write!(f, "log does not match event schema"),
+            Self::TopicNotFound => write!(f, "topic does not exist"),
+            Self::DataNotFound => write!(f, "log data does not exist"),
+            Self::InvalidCalldata => write!(f, "calldata is too short to have a function selector"),
+            Self::InvalidCalldataIdx => write!(f, "calldata index out of range"),
+            Self::InvalidContractDataIdx => write!(f, "contract data index out of range"),
+            Self::GasPriceNotAvailable => {
+                write!(f, "gas price is not available for EIP-1559 transactions")
+            }
+            Self::UseLogIdx => write!(f, "use a log index instead of the logs field"),
+            Self::InvalidMappingDepth(depth) => {
+                write!(f, "mapping depth {depth} is more than the number of keys")
+            }
+            Self::FieldNotAvailable(field) => {
+                write!(f, "{field} is not available for this block or transaction")
+            }
+            Self::UnsupportedTxType(tx_type) => write!(f, "unsupported transaction type {tx_type}"),
+            Self::TxDataTooLong { len, max } => {
+                write!(
+                    f,
+                    "transaction data is {len} bytes, more than the limit of {max}"
+                )
+            }
+            Self::AccessListTooLong { len, max } => {
+                write!(
+                    f,
+                    "access list is {len} bytes, more than the limit of {max}"
+                )
+            }
+            Self::TooManyLogs { num, max } => {
+                write!(f, "receipt has {num} logs, more than the limit of {max}")
+            }
+            Self::LogDataTooLong { len, max } => {
+                write!(f, "log data is {len} bytes, more than the limit of {max}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvalidSubqueryReason {}
+
+/// A subquery that could not be answered during witness generation
+#[derive(Clone, Debug)]
+pub enum SubqueryError {
+    /// The subquery does not refer to valid chain data
+    Invalid {
+        subquery: AnySubquery,
+        reason: InvalidSubqueryReason,
+    },
+    /// The data source failed to answer the subquery
+    DataSource {
+        subquery: AnySubquery,
+        message: String,
+    },
+}
+
+impl SubqueryError {
+    /// Classifies an error returned by a [SubqueryDataSource](super::source::SubqueryDataSource)
+    pub fn new(subquery: AnySubquery, err: anyhow::Error) -> Self {
+        match err.downcast_ref::<InvalidSubqueryReason>() {
+            Some(reason) => Self::Invalid {
+                subquery,
+                reason: reason.clone(),
+            },
+            None => Self::DataSource {
+                subquery,
+                message: format!("{err:#}"),
+            },
+        }
+    }
+
+    pub fn subquery(&self) -> &AnySubquery {
+        match self {
+            Self::Invalid { subquery, .. } | Self::DataSource { subquery, .. } => subquery,
+        }
+    }
+}
+
+impl fmt::Display for SubqueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid { subquery, reason } => {
+                write!(f, "invalid subquery {subquery:?}: {reason}")
+            }
+            Self::DataSource { subquery, message } => {
+                write!(f, "failed to fetch subquery {subquery:?}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubqueryError {}
+
+/// All the subquery errors from one witness generation run
+#[derive(Clone, Debug)]
+pub struct SubqueryErrors(pub Vec<SubqueryError>);
+
+impl fmt::Display for SubqueryErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} subquery call(s) failed", self.0.len())?;
+        for err in self.0.iter() {
+            write!(f, "\n  {err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SubqueryErrors {}
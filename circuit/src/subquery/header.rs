@@ -1,4 +1,4 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use axiom_codec::{
     special_values::{
         HEADER_EXTRA_DATA_LEN_FIELD_IDX, HEADER_HASH_FIELD_IDX, HEADER_HEADER_SIZE_FIELD_IDX,
@@ -9,13 +9,15 @@ use axiom_codec::{
 use axiom_query::axiom_eth::{halo2_base::AssignedValue, Field};
 use ethers::{
     providers::{JsonRpcClient, Middleware, Provider},
-    types::{BigEndianHash, BlockId, H256},
+    types::{BigEndianHash, Block, BlockId, H256},
 };
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
-use tokio::runtime::Runtime;
 
-use super::{caller::FetchSubquery, types::AssignedHeaderSubquery, utils::pad_to_bytes32};
+use super::{
+    caller::FetchSubquery, error::InvalidSubqueryReason, source::SubqueryDataSource,
+    types::AssignedHeaderSubquery, utils::pad_to_bytes32,
+};
 use crate::impl_fr_from;
 
 #[derive(FromPrimitive, Clone)]
@@ -37,6 +39,9 @@ pub enum HeaderField {
     Nonce,
     BaseFeePerGas,
     WithdrawalsRoot,
+    BlobGasUsed,
+    ExcessBlobGas,
+    ParentBeaconBlockRoot,
     Hash = HEADER_HASH_FIELD_IDX as isize,
     Size = HEADER_HEADER_SIZE_FIELD_IDX as isize,
     ExtraDataLen = HEADER_EXTRA_DATA_LEN_FIELD_IDX as isize,
@@ -48,12 +53,20 @@ pub async fn get_header_field_value<P: JsonRpcClient>(
     query: HeaderSubquery,
 ) -> Result<H256> {
     let block_id = BlockId::from(query.block_number as u64);
-    let block = provider.get_block(block_id).await?;
-    if block.is_none() {
-        bail!("Block does not exist")
-    }
-    let block = block.unwrap();
+    let block = provider
+        .get_block(block_id)
+        .await?
+        .ok_or(InvalidSubqueryReason::BlockNotFound)?;
+    get_header_field_value_from_block(block, &query)
+}
 
+/// Returns the value a header subquery resolves to, given the block it refers to
+///
+/// Fields that were added by a fork after the block are not available.
+pub fn get_header_field_value_from_block<TX>(
+    block: Block<TX>,
+    query: &HeaderSubquery,
+) -> Result<H256> {
     let field_idx = query.field_idx as usize;
 
     if (HEADER_LOGS_BLOOM_FIELD_IDX_OFFSET..HEADER_LOGS_BLOOM_FIELD_IDX_OFFSET + 8)
@@ -64,11 +77,12 @@ pub async fn get_header_field_value<P: JsonRpcClient>(
         let bloom_bytes = &bloom[log_idx..log_idx + 32];
         return Ok(H256::from_slice(bloom_bytes));
     }
-    let header_field_idx = HeaderField::from_usize(field_idx).expect("Invalid field index");
+    let header_field_idx = HeaderField::from_usize(field_idx)
+        .ok_or(InvalidSubqueryReason::InvalidFieldIdx(query.field_idx))?;
     let val = match header_field_idx {
         HeaderField::ParentHash => block.parent_hash,
         HeaderField::Sha3Uncles => block.uncles_hash,
-        HeaderField::Miner => H256::from_slice(block.author.unwrap().as_bytes()),
+        HeaderField::Miner => H256::from(block.author.unwrap()),
         HeaderField::StateRoot => block.state_root,
         HeaderField::TransactionsRoot => block.transactions_root,
         HeaderField::ReceiptsRoot => block.receipts_root,
@@ -87,8 +101,31 @@ pub async fn get_header_field_value<P: JsonRpcClient>(
         }
         HeaderField::MixHash => block.mix_hash.unwrap(),
         HeaderField::Nonce => H256::from_slice(&block.nonce.unwrap().to_fixed_bytes()),
-        HeaderField::BaseFeePerGas => H256::from_uint(&block.base_fee_per_gas.unwrap()),
-        HeaderField::WithdrawalsRoot => block.withdrawals_root.unwrap(),
+        HeaderField::BaseFeePerGas => H256::from_uint(
+            &block
+                .base_fee_per_gas
+                .ok_or(InvalidSubqueryReason::FieldNotAvailable("baseFeePerGas"))?,
+        ),
+        HeaderField::WithdrawalsRoot => block
+            .withdrawals_root
+            .ok_or(InvalidSubqueryReason::FieldNotAvailable("withdrawalsRoot"))?,
+        HeaderField::BlobGasUsed => H256::from_uint(
+            &block
+                .blob_gas_used
+                .ok_or(InvalidSubqueryReason::FieldNotAvailable("blobGasUsed"))?,
+        ),
+        HeaderField::ExcessBlobGas => H256::from_uint(
+            &block
+                .excess_blob_gas
+                .ok_or(InvalidSubqueryReason::FieldNotAvailable("excessBlobGas"))?,
+        ),
+        HeaderField::ParentBeaconBlockRoot => {
+            block
+                .parent_beacon_block_root
+                .ok_or(InvalidSubqueryReason::FieldNotAvailable(
+                    "parentBeaconBlockRoot",
+                ))?
+        }
         HeaderField::Hash => block.hash.unwrap(),
         HeaderField::Size => H256::from_uint(&block.size.unwrap()),
         HeaderField::ExtraDataLen => H256::from_low_u64_be(block.extra_data.len() as u64),
@@ -98,10 +135,8 @@ pub async fn get_header_field_value<P: JsonRpcClient>(
 }
 
 impl<F: Field> FetchSubquery<F> for AssignedHeaderSubquery<F> {
-    fn fetch<P: JsonRpcClient>(&self, p: &Provider<P>) -> Result<H256> {
-        let rt = Runtime::new()?;
-        let val = rt.block_on(get_header_field_value(p, (*self).into()))?;
-        Ok(val)
+    fn fetch<P: SubqueryDataSource>(&self, p: &P) -> Result<H256> {
+        p.get_header_field_value((*self).into())
     }
 
     fn any_subquery(&self) -> AnySubquery {
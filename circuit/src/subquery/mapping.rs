@@ -2,24 +2,27 @@ use anyhow::Result;
 use axiom_codec::types::native::{AnySubquery, SolidityNestedMappingSubquery, StorageSubquery};
 use axiom_query::axiom_eth::{halo2_base::AssignedValue, Field};
 use ethers::{
-    providers::{JsonRpcClient, Provider},
     types::{BigEndianHash, H256},
     utils::keccak256,
 };
-use tokio::runtime::Runtime;
 
 use super::{
-    caller::FetchSubquery, storage::get_storage_field_value,
+    caller::FetchSubquery, error::InvalidSubqueryReason, source::SubqueryDataSource,
     types::AssignedSolidityNestedMappingSubquery,
 };
 
-pub async fn get_solidity_nested_mapping_field_value<P: JsonRpcClient>(
-    provider: &Provider<P>,
-    query: SolidityNestedMappingSubquery,
-) -> Result<H256> {
+/// Returns the storage subquery for the slot that a Solidity nested mapping subquery resolves to
+pub fn get_solidity_nested_mapping_storage_subquery(
+    query: &SolidityNestedMappingSubquery,
+) -> Result<StorageSubquery> {
     let mut slot = H256::from_uint(&query.mapping_slot);
     for i in 0..query.mapping_depth {
-        let key = query.keys.get(i as usize).unwrap();
+        let key = query
+            .keys
+            .get(i as usize)
+            .ok_or(InvalidSubqueryReason::InvalidMappingDepth(
+                query.mapping_depth,
+            ))?;
         let concat_h256 = key
             .as_bytes()
             .iter()
@@ -29,20 +32,16 @@ pub async fn get_solidity_nested_mapping_field_value<P: JsonRpcClient>(
         slot = H256::from(keccak256(concat_h256));
     }
 
-    let storage_query = StorageSubquery {
+    Ok(StorageSubquery {
         block_number: query.block_number,
         addr: query.addr,
         slot: slot.into_uint(),
-    };
-
-    get_storage_field_value(provider, storage_query).await
+    })
 }
 
 impl<F: Field> FetchSubquery<F> for AssignedSolidityNestedMappingSubquery<F> {
-    fn fetch<P: JsonRpcClient>(&self, p: &Provider<P>) -> Result<H256> {
-        let rt = Runtime::new()?;
-        let val = rt.block_on(get_solidity_nested_mapping_field_value(p, (*self).into()))?;
-        Ok(val)
+    fn fetch<P: SubqueryDataSource>(&self, p: &P) -> Result<H256> {
+        p.get_solidity_nested_mapping_field_value((*self).into())
     }
 
     fn any_subquery(&self) -> AnySubquery {
@@ -0,0 +1,176 @@
+use std::{collections::BTreeMap, fs::File, path::Path};
+
+use anyhow::Result;
+use axiom_codec::{
+    special_values::{RECEIPT_ADDRESS_IDX, RECEIPT_LOG_IDX_OFFSET},
+    types::native::AnySubquery,
+};
+use ethers::{
+    types::{BigEndianHash, H256, U256},
+    utils::keccak256,
+};
+use num_traits::FromPrimitive;
+
+use super::{
+    source::SubqueryFixtureEntry, utils::get_subquery_type_from_any_subquery, AccountField,
+    HeaderField, ReceiptField, TxField,
+};
+
+/// How subqueries are answered when the circuit is run without inputs, e.g. during keygen
+#[derive(Clone, Debug, Default)]
+pub enum MockStrategy {
+    /// Every subquery returns zero
+    #[default]
+    Zero,
+    /// Every subquery returns a non-zero pseudo-random value derived from `seed` and the
+    /// subquery, no wider than the field it queries (e.g. 160 bits for addresses)
+    Random { seed: u64 },
+    /// Subqueries are answered from a sample fixture
+    Fixture(MockFixture),
+}
+
+impl MockStrategy {
+    pub fn mock_value(&self, subquery: &AnySubquery) -> H256 {
+        match self {
+            Self::Zero => H256::zero(),
+            Self::Random { seed } => get_random_mock_value(*seed, subquery),
+            Self::Fixture(fixture) => fixture.get(subquery),
+        }
+    }
+}
+
+/// Mock subquery results taken from a sample run.
+///
+/// The inputs of a mock run usually differ from those of the sample, so a subquery that is not in
+/// the fixture is answered with the result of a subquery of the same type and field, or zero if
+/// there is none.
+#[derive(Clone, Debug, Default)]
+pub struct MockFixture {
+    results: BTreeMap<AnySubquery, H256>,
+    field_results: BTreeMap<(u64, u64), H256>,
+}
+
+impl MockFixture {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let f = File::open(path)?;
+        let entries: Vec<SubqueryFixtureEntry> = serde_json::from_reader(f)?;
+        Ok(entries.into())
+    }
+
+    pub fn get(&self, subquery: &AnySubquery) -> H256 {
+        self.results
+            .get(subquery)
+            .or_else(|| self.field_results.get(&get_mock_field_key(subquery)))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl From<Vec<SubqueryFixtureEntry>> for MockFixture {
+    fn from(entries: Vec<SubqueryFixtureEntry>) -> Self {
+        let mut fixture = Self::default();
+        for entry in entries {
+            fixture
+                .field_results
+                .entry(get_mock_field_key(&entry.subquery))
+                .or_insert(entry.value);
+            fixture.results.insert(entry.subquery, entry.value);
+        }
+        fixture
+    }
+}
+
+/// Returns the subquery type and the field it queries, ignoring the block, account or slot
+fn get_mock_field_key(subquery: &AnySubquery) -> (u64, u64) {
+    let field_idx = match subquery {
+        AnySubquery::Header(query) => query.field_idx as u64,
+        AnySubquery::Account(query) => query.field_idx as u64,
+        AnySubquery::Transaction(query) => query.field_or_calldata_idx as u64,
+        AnySubquery::Receipt(query) => {
+            ((query.field_or_log_idx as u64) << 32) + query.topic_or_data_or_address_idx as u64
+        }
+        _ => 0,
+    };
+    (get_subquery_type_from_any_subquery(subquery), field_idx)
+}
+
+/// Returns the number of bits a realistic result of `subquery` fits in
+fn get_mock_bits(subquery: &AnySubquery) -> usize {
+    match subquery {
+        AnySubquery::Header(query) => match HeaderField::from_u32(query.field_idx) {
+            Some(HeaderField::Miner) => 160,
+            Some(
+                HeaderField::Difficulty
+                | HeaderField::Number
+                | HeaderField::GasLimit
+                | HeaderField::GasUsed
+                | HeaderField::Timestamp
+                | HeaderField::Nonce
+                | HeaderField::BaseFeePerGas
+                | HeaderField::BlobGasUsed
+                | HeaderField::ExcessBlobGas
+                | HeaderField::Size
+                | HeaderField::ExtraDataLen,
+            ) => 64,
+            _ => 256,
+        },
+        AnySubquery::Account(query) => match AccountField::from_u32(query.field_idx) {
+            Some(AccountField::Nonce) => 64,
+            Some(AccountField::Balance) => 128,
+            _ => 256,
+        },
+        AnySubquery::Transaction(query) => match TxField::from_u32(query.field_or_calldata_idx) {
+            Some(TxField::TxType) => 2,
+            Some(TxField::To) => 160,
+            Some(TxField::Value) => 128,
+            Some(TxField::FunctionSelector) => 32,
+            Some(
+                TxField::ChainId
+                | TxField::Nonce
+                | TxField::MaxPriorityFeePerGas
+                | TxField::MaxFeePerGas
+                | TxField::GasLimit
+                | TxField::GasPrice
+                | TxField::V
+                | TxField::BlockNumber
+                | TxField::TxIndex
+                | TxField::DataLength,
+            ) => 64,
+            _ => 256,
+        },
+        AnySubquery::Receipt(query) => {
+            if query.field_or_log_idx >= RECEIPT_LOG_IDX_OFFSET as u32 {
+                if query.topic_or_data_or_address_idx == RECEIPT_ADDRESS_IDX as u32 {
+                    160
+                } else {
+                    256
+                }
+            } else {
+                match ReceiptField::from_u32(query.field_or_log_idx) {
+                    Some(ReceiptField::Status) => 1,
+                    Some(ReceiptField::TxType) => 2,
+                    Some(
+                        ReceiptField::CumulativeGas
+                        | ReceiptField::BlockNumber
+                        | ReceiptField::TxIndex,
+                    ) => 64,
+                    _ => 256,
+                }
+            }
+        }
+        _ => 256,
+    }
+}
+
+fn get_random_mock_value(seed: u64, subquery: &AnySubquery) -> H256 {
+    let mut preimage = seed.to_be_bytes().to_vec();
+    preimage.extend(serde_json::to_vec(subquery).unwrap());
+    let value = U256::from_big_endian(&keccak256(preimage));
+    let bits = get_mock_bits(subquery);
+    let value = if bits < 256 {
+        value & ((U256::one() << bits) - 1)
+    } else {
+        value
+    };
+    H256::from_uint(&value.max(U256::one()))
+}
@@ -10,7 +10,12 @@ pub use header::HeaderField;
 pub use receipt::ReceiptField;
 pub use tx::TxField;
 
+pub mod cache;
 pub mod caller;
+pub mod error;
 pub mod keccak;
+pub mod mock;
+pub mod source;
 pub mod types;
 pub mod utils;
+pub mod verify;
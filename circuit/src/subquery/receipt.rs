@@ -10,13 +10,15 @@ use axiom_codec::{
 use axiom_query::axiom_eth::{halo2_base::AssignedValue, Field};
 use ethers::{
     providers::{JsonRpcClient, Middleware, Provider},
-    types::{BigEndianHash, BlockId, H256},
+    types::{BigEndianHash, BlockId, TransactionReceipt, H256},
 };
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
-use tokio::runtime::Runtime;
 
-use super::{caller::FetchSubquery, types::AssignedReceiptSubquery, utils::pad_to_bytes32};
+use super::{
+    caller::FetchSubquery, error::InvalidSubqueryReason, source::SubqueryDataSource,
+    types::AssignedReceiptSubquery, utils::pad_to_bytes32,
+};
 use crate::impl_fr_from;
 
 #[derive(FromPrimitive)]
@@ -39,10 +41,20 @@ pub async fn get_receipt_field_value<P: JsonRpcClient>(
     let block_id = BlockId::from(query.block_number as u64);
     let tx = provider
         .get_transaction_by_block_and_index(block_id, query.tx_idx.into())
-        .await?;
-    let tx_hash = tx.unwrap().hash;
-    let receipt = provider.get_transaction_receipt(tx_hash).await?.unwrap();
-    //todo: check receipt size
+        .await?
+        .ok_or(InvalidSubqueryReason::TxNotFound)?;
+    let receipt = provider
+        .get_transaction_receipt(tx.hash)
+        .await?
+        .ok_or(InvalidSubqueryReason::ReceiptNotFound)?;
+    get_receipt_field_value_from_receipt(receipt, &query)
+}
+
+/// Returns the value a receipt subquery resolves to, given the receipt it refers to
+pub fn get_receipt_field_value_from_receipt(
+    receipt: TransactionReceipt,
+    query: &ReceiptSubquery,
+) -> Result<H256> {
     let field_or_log_idx = query.field_or_log_idx as usize;
     if (RECEIPT_LOGS_BLOOM_IDX_OFFSET..RECEIPT_LOGS_BLOOM_IDX_OFFSET + 8)
         .contains(&field_or_log_idx)
@@ -55,12 +67,12 @@ pub async fn get_receipt_field_value<P: JsonRpcClient>(
     if field_or_log_idx >= RECEIPT_LOG_IDX_OFFSET {
         let log_idx = field_or_log_idx - RECEIPT_LOG_IDX_OFFSET;
         if log_idx >= receipt.logs.len() {
-            bail!("Log does not exist")
+            bail!(InvalidSubqueryReason::LogNotFound)
         }
         let log = receipt.logs[log_idx].clone();
         let topics = log.topics;
-        if query.event_schema != H256::zero() && query.event_schema != topics[0] {
-            bail!("Log does not match event schema")
+        if query.event_schema != H256::zero() && topics.first() != Some(&query.event_schema) {
+            bail!(InvalidSubqueryReason::EventSchemaMismatch)
         }
 
         let topic_or_data_or_address_idx = query.topic_or_data_or_address_idx as usize;
@@ -69,7 +81,7 @@ pub async fn get_receipt_field_value<P: JsonRpcClient>(
             return Ok(log.address.into());
         } else if topic_or_data_or_address_idx < RECEIPT_DATA_IDX_OFFSET {
             if topic_or_data_or_address_idx > topics.len() {
-                bail!("Topic does not exist")
+                bail!(InvalidSubqueryReason::TopicNotFound)
             }
 
             if topic_or_data_or_address_idx < topics.len() {
@@ -82,28 +94,47 @@ pub async fn get_receipt_field_value<P: JsonRpcClient>(
         } else {
             let data_idx = topic_or_data_or_address_idx - RECEIPT_DATA_IDX_OFFSET;
             if data_idx >= log.data.len() / 32 {
-                bail!("Data does not exist")
+                bail!(InvalidSubqueryReason::DataNotFound)
             }
             let data_bytes = &log.data[data_idx * 32..(data_idx + 1) * 32];
             return Ok(H256::from_slice(data_bytes));
         }
     }
 
-    let receipt_field_idx =
-        ReceiptField::from_usize(field_or_log_idx).expect("Invalid field index");
+    let receipt_field_idx = ReceiptField::from_usize(field_or_log_idx).ok_or(
+        InvalidSubqueryReason::InvalidFieldIdx(query.field_or_log_idx),
+    )?;
     let val = match receipt_field_idx {
-        ReceiptField::Status => H256::from_low_u64_be(receipt.status.unwrap().as_u64()),
-        ReceiptField::PostState => receipt.root.unwrap(),
+        ReceiptField::Status => {
+            let status = receipt
+                .status
+                .ok_or(InvalidSubqueryReason::FieldNotAvailable("status"))?;
+            H256::from_low_u64_be(status.as_u64())
+        }
+        // only pre-Byzantium receipts have a post-transaction state root
+        ReceiptField::PostState => receipt
+            .root
+            .ok_or(InvalidSubqueryReason::FieldNotAvailable("root"))?,
         ReceiptField::CumulativeGas => H256::from_uint(&receipt.cumulative_gas_used),
         ReceiptField::LogsBloom => {
             let logs_bloom = receipt.logs_bloom;
             H256::from(pad_to_bytes32(logs_bloom.as_fixed_bytes()))
         }
         ReceiptField::Logs => {
-            bail!("Use log idx instead of logs field")
+            bail!(InvalidSubqueryReason::UseLogIdx)
+        }
+        ReceiptField::TxType => {
+            let tx_type = receipt
+                .transaction_type
+                .ok_or(InvalidSubqueryReason::FieldNotAvailable("type"))?;
+            H256::from_low_u64_be(tx_type.as_u64())
+        }
+        ReceiptField::BlockNumber => {
+            let block_number = receipt
+                .block_number
+                .ok_or(InvalidSubqueryReason::FieldNotAvailable("blockNumber"))?;
+            H256::from_low_u64_be(block_number.as_u64())
         }
-        ReceiptField::TxType => H256::from_low_u64_be(receipt.transaction_type.unwrap().as_u64()),
-        ReceiptField::BlockNumber => H256::from_low_u64_be(receipt.block_number.unwrap().as_u64()),
         ReceiptField::TxIndex => H256::from_low_u64_be(receipt.transaction_index.as_u64()),
     };
 
@@ -111,10 +142,8 @@ pub async fn get_receipt_field_value<P: JsonRpcClient>(
 }
 
 impl<F: Field> FetchSubquery<F> for AssignedReceiptSubquery<F> {
-    fn fetch<P: JsonRpcClient>(&self, p: &Provider<P>) -> Result<H256> {
-        let rt = Runtime::new()?;
-        let val = rt.block_on(get_receipt_field_value(p, (*self).into()))?;
-        Ok(val)
+    fn fetch<P: SubqueryDataSource>(&self, p: &P) -> Result<H256> {
+        p.get_receipt_field_value((*self).into())
     }
 
     fn any_subquery(&self) -> AnySubquery {
@@ -0,0 +1,324 @@
+use std::{collections::BTreeMap, fmt::Debug, fs::File, path::Path, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use axiom_codec::types::native::{
+    AccountSubquery, AnySubquery, HeaderSubquery, ReceiptSubquery, SolidityNestedMappingSubquery,
+    StorageSubquery, TxSubquery,
+};
+use ethers::{
+    providers::{JsonRpcClient, Provider},
+    types::H256,
+};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Runtime;
+
+use super::{
+    account::get_account_field_value, header::get_header_field_value,
+    mapping::get_solidity_nested_mapping_storage_subquery, receipt::get_receipt_field_value,
+    storage::get_storage_field_value, tx::get_tx_field_value,
+};
+
+/// A source of native subquery results.
+///
+/// [SubqueryCaller](super::caller::SubqueryCaller) is generic over this trait, so subqueries can
+/// be answered by a JSON-RPC provider, an in-memory table or a JSON fixture.
+pub trait SubqueryDataSource: Debug + Send + Sync {
+    fn get_header_field_value(&self, query: HeaderSubquery) -> Result<H256>;
+    fn get_account_field_value(&self, query: AccountSubquery) -> Result<H256>;
+    fn get_storage_field_value(&self, query: StorageSubquery) -> Result<H256>;
+    fn get_tx_field_value(&self, query: TxSubquery) -> Result<H256>;
+    fn get_receipt_field_value(&self, query: ReceiptSubquery) -> Result<H256>;
+
+    /// Defaults to a storage lookup of the slot the nested mapping resolves to.
+    fn get_solidity_nested_mapping_field_value(
+        &self,
+        query: SolidityNestedMappingSubquery,
+    ) -> Result<H256> {
+        self.get_storage_field_value(get_solidity_nested_mapping_storage_subquery(&query)?)
+    }
+
+    fn fetch(&self, subquery: AnySubquery) -> Result<H256> {
+        match subquery {
+            AnySubquery::Null => Ok(H256::zero()),
+            AnySubquery::Header(query) => self.get_header_field_value(query),
+            AnySubquery::Account(query) => self.get_account_field_value(query),
+            AnySubquery::Storage(query) => self.get_storage_field_value(query),
+            AnySubquery::Transaction(query) => self.get_tx_field_value(query),
+            AnySubquery::Receipt(query) => self.get_receipt_field_value(query),
+            AnySubquery::SolidityNestedMapping(query) => {
+                self.get_solidity_nested_mapping_field_value(query)
+            }
+        }
+    }
+
+    /// Fetches the results of `subqueries`, with at most `parallelism` requests in flight
+    ///
+    /// The default implementation fetches them one at a time.
+    #[allow(unused_variables)]
+    fn fetch_many(&self, subqueries: Vec<AnySubquery>, parallelism: usize) -> Vec<Result<H256>> {
+        subqueries
+            .into_iter()
+            .map(|subquery| self.fetch(subquery))
+            .collect()
+    }
+}
+
+/// A type-erased, cheaply cloneable [SubqueryDataSource]
+pub type SharedDataSource = Arc<dyn SubqueryDataSource>;
+
+impl<S: SubqueryDataSource + ?Sized> SubqueryDataSource for Arc<S> {
+    fn get_header_field_value(&self, query: HeaderSubquery) -> Result<H256> {
+        (**self).get_header_field_value(query)
+    }
+
+    fn get_account_field_value(&self, query: AccountSubquery) -> Result<H256> {
+        (**self).get_account_field_value(query)
+    }
+
+    fn get_storage_field_value(&self, query: StorageSubquery) -> Result<H256> {
+        (**self).get_storage_field_value(query)
+    }
+
+    fn get_tx_field_value(&self, query: TxSubquery) -> Result<H256> {
+        (**self).get_tx_field_value(query)
+    }
+
+    fn get_receipt_field_value(&self, query: ReceiptSubquery) -> Result<H256> {
+        (**self).get_receipt_field_value(query)
+    }
+
+    fn get_solidity_nested_mapping_field_value(
+        &self,
+        query: SolidityNestedMappingSubquery,
+    ) -> Result<H256> {
+        (**self).get_solidity_nested_mapping_field_value(query)
+    }
+
+    fn fetch(&self, subquery: AnySubquery) -> Result<H256> {
+        (**self).fetch(subquery)
+    }
+
+    fn fetch_many(&self, subqueries: Vec<AnySubquery>, parallelism: usize) -> Vec<Result<H256>> {
+        (**self).fetch_many(subqueries, parallelism)
+    }
+}
+
+/// Fetches the result of any subquery over JSON-RPC
+pub async fn get_subquery_field_value<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    subquery: AnySubquery,
+) -> Result<H256> {
+    match subquery {
+        AnySubquery::Null => Ok(H256::zero()),
+        AnySubquery::Header(query) => get_header_field_value(provider, query).await,
+        AnySubquery::Account(query) => get_account_field_value(provider, query).await,
+        AnySubquery::Storage(query) => get_storage_field_value(provider, query).await,
+        AnySubquery::Transaction(query) => get_tx_field_value(provider, query).await,
+        AnySubquery::Receipt(query) => get_receipt_field_value(provider, query).await,
+        AnySubquery::SolidityNestedMapping(query) => {
+            let storage_query = get_solidity_nested_mapping_storage_subquery(&query)?;
+            get_storage_field_value(provider, storage_query).await
+        }
+    }
+}
+
+/// Fetches subquery results over JSON-RPC
+impl<P: JsonRpcClient> SubqueryDataSource for Provider<P> {
+    fn get_header_field_value(&self, query: HeaderSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        rt.block_on(get_header_field_value(self, query))
+    }
+
+    fn get_account_field_value(&self, query: AccountSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        rt.block_on(get_account_field_value(self, query))
+    }
+
+    fn get_storage_field_value(&self, query: StorageSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        rt.block_on(get_storage_field_value(self, query))
+    }
+
+    fn get_tx_field_value(&self, query: TxSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        rt.block_on(get_tx_field_value(self, query))
+    }
+
+    fn get_receipt_field_value(&self, query: ReceiptSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        rt.block_on(get_receipt_field_value(self, query))
+    }
+
+    /// Fetches all subqueries concurrently on a single runtime
+    fn fetch_many(&self, subqueries: Vec<AnySubquery>, parallelism: usize) -> Vec<Result<H256>> {
+        let rt = match Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                return subqueries
+                    .iter()
+                    .map(|_| Err(anyhow!("Failed to start runtime: {e}")))
+                    .collect()
+            }
+        };
+        rt.block_on(
+            stream::iter(subqueries)
+                .map(|subquery| get_subquery_field_value(self, subquery))
+                .buffered(parallelism.max(1))
+                .collect(),
+        )
+    }
+}
+
+/// A single subquery and its result, as stored in a JSON fixture
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SubqueryFixtureEntry {
+    pub subquery: AnySubquery,
+    pub value: H256,
+}
+
+/// Answers subqueries from an in-memory table
+///
+/// Solidity nested mapping subqueries that are not in the table fall back to a lookup of the
+/// storage slot they resolve to.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryDataSource {
+    results: BTreeMap<AnySubquery, H256>,
+}
+
+impl InMemoryDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, subquery: impl Into<AnySubquery>, value: H256) {
+        self.results.insert(subquery.into(), value);
+    }
+
+    pub fn with(mut self, subquery: impl Into<AnySubquery>, value: H256) -> Self {
+        self.insert(subquery, value);
+        self
+    }
+
+    pub fn entries(&self) -> Vec<SubqueryFixtureEntry> {
+        self.results
+            .iter()
+            .map(|(subquery, value)| SubqueryFixtureEntry {
+                subquery: subquery.clone(),
+                value: *value,
+            })
+            .collect()
+    }
+
+    fn get(&self, subquery: AnySubquery) -> Result<H256> {
+        self.results
+            .get(&subquery)
+            .copied()
+            .ok_or_else(|| anyhow!("Subquery not found in data source: {:?}", subquery))
+    }
+}
+
+impl FromIterator<SubqueryFixtureEntry> for InMemoryDataSource {
+    fn from_iter<T: IntoIterator<Item = SubqueryFixtureEntry>>(iter: T) -> Self {
+        Self {
+            results: iter
+                .into_iter()
+                .map(|entry| (entry.subquery, entry.value))
+                .collect(),
+        }
+    }
+}
+
+impl SubqueryDataSource for InMemoryDataSource {
+    fn get_header_field_value(&self, query: HeaderSubquery) -> Result<H256> {
+        self.get(AnySubquery::Header(query))
+    }
+
+    fn get_account_field_value(&self, query: AccountSubquery) -> Result<H256> {
+        self.get(AnySubquery::Account(query))
+    }
+
+    fn get_storage_field_value(&self, query: StorageSubquery) -> Result<H256> {
+        self.get(AnySubquery::Storage(query))
+    }
+
+    fn get_tx_field_value(&self, query: TxSubquery) -> Result<H256> {
+        self.get(AnySubquery::Transaction(query))
+    }
+
+    fn get_receipt_field_value(&self, query: ReceiptSubquery) -> Result<H256> {
+        self.get(AnySubquery::Receipt(query))
+    }
+
+    fn get_solidity_nested_mapping_field_value(
+        &self,
+        query: SolidityNestedMappingSubquery,
+    ) -> Result<H256> {
+        let storage_query = get_solidity_nested_mapping_storage_subquery(&query)?;
+        self.get(AnySubquery::SolidityNestedMapping(query))
+            .or_else(|_| self.get_storage_field_value(storage_query))
+    }
+}
+
+/// Answers subqueries from a JSON fixture file containing a list of [SubqueryFixtureEntry]
+#[derive(Clone, Debug, Default)]
+pub struct JsonFixtureDataSource {
+    inner: InMemoryDataSource,
+}
+
+impl JsonFixtureDataSource {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let f = File::open(path)?;
+        let entries: Vec<SubqueryFixtureEntry> = serde_json::from_reader(f)?;
+        Ok(Self::from(entries))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let entries: Vec<SubqueryFixtureEntry> = serde_json::from_str(json)?;
+        Ok(Self::from(entries))
+    }
+
+    /// Writes `entries` to `path` in the format read by [JsonFixtureDataSource::from_path]
+    pub fn write(path: impl AsRef<Path>, entries: &[SubqueryFixtureEntry]) -> Result<()> {
+        let f = File::create(path)?;
+        serde_json::to_writer_pretty(f, entries)?;
+        Ok(())
+    }
+}
+
+impl From<Vec<SubqueryFixtureEntry>> for JsonFixtureDataSource {
+    fn from(entries: Vec<SubqueryFixtureEntry>) -> Self {
+        Self {
+            inner: entries.into_iter().collect(),
+        }
+    }
+}
+
+impl SubqueryDataSource for JsonFixtureDataSource {
+    fn get_header_field_value(&self, query: HeaderSubquery) -> Result<H256> {
+        self.inner.get_header_field_value(query)
+    }
+
+    fn get_account_field_value(&self, query: AccountSubquery) -> Result<H256> {
+        self.inner.get_account_field_value(query)
+    }
+
+    fn get_storage_field_value(&self, query: StorageSubquery) -> Result<H256> {
+        self.inner.get_storage_field_value(query)
+    }
+
+    fn get_tx_field_value(&self, query: TxSubquery) -> Result<H256> {
+        self.inner.get_tx_field_value(query)
+    }
+
+    fn get_receipt_field_value(&self, query: ReceiptSubquery) -> Result<H256> {
+        self.inner.get_receipt_field_value(query)
+    }
+
+    fn get_solidity_nested_mapping_field_value(
+        &self,
+        query: SolidityNestedMappingSubquery,
+    ) -> Result<H256> {
+        self.inner.get_solidity_nested_mapping_field_value(query)
+    }
+}
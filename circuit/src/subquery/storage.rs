@@ -5,9 +5,8 @@ use ethers::{
     providers::{JsonRpcClient, Middleware, Provider},
     types::{BigEndianHash, BlockId, H256},
 };
-use tokio::runtime::Runtime;
 
-use super::{caller::FetchSubquery, types::AssignedStorageSubquery};
+use super::{caller::FetchSubquery, source::SubqueryDataSource, types::AssignedStorageSubquery};
 
 pub async fn get_storage_field_value<P: JsonRpcClient>(
     provider: &Provider<P>,
@@ -22,10 +21,8 @@ pub async fn get_storage_field_value<P: JsonRpcClient>(
 }
 
 impl<F: Field> FetchSubquery<F> for AssignedStorageSubquery<F> {
-    fn fetch<P: JsonRpcClient>(&self, p: &Provider<P>) -> Result<H256> {
-        let rt = Runtime::new()?;
-        let val = rt.block_on(get_storage_field_value(p, (*self).into()))?;
-        Ok(val)
+    fn fetch<P: SubqueryDataSource>(&self, p: &P) -> Result<H256> {
+        p.get_storage_field_value((*self).into())
     }
 
     fn any_subquery(&self) -> AnySubquery {
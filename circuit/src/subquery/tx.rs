@@ -1,4 +1,4 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use axiom_codec::{
     special_values::{
         TX_BLOCK_NUMBER_FIELD_IDX, TX_CALLDATA_HASH_FIELD_IDX, TX_CALLDATA_IDX_OFFSET,
@@ -11,14 +11,16 @@ use axiom_codec::{
 use axiom_query::axiom_eth::{halo2_base::AssignedValue, Field};
 use ethers::{
     providers::{JsonRpcClient, Middleware, Provider},
-    types::{BigEndianHash, BlockId, H256, U64},
+    types::{BigEndianHash, BlockId, Transaction, H256, U64},
     utils::keccak256,
 };
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
-use tokio::runtime::Runtime;
 
-use super::{caller::FetchSubquery, types::AssignedTxSubquery, utils::pad_to_bytes32};
+use super::{
+    caller::FetchSubquery, error::InvalidSubqueryReason, source::SubqueryDataSource,
+    types::AssignedTxSubquery, utils::pad_to_bytes32,
+};
 use crate::impl_fr_from;
 
 #[derive(FromPrimitive)]
@@ -51,69 +53,88 @@ pub async fn get_tx_field_value<P: JsonRpcClient>(
     let block_id = BlockId::from(query.block_number as u64);
     let tx = provider
         .get_transaction_by_block_and_index(block_id, U64::from(query.tx_idx))
-        .await;
-    if tx.is_err() {
-        bail!("Provider Error: Couldn't Fetch Tx")
-    }
-    let tx = tx.unwrap();
-    if tx.is_none() {
-        bail!("Transaction does not exist")
-    }
-    let tx = tx.unwrap();
-    //todo: validate tx size
+        .await
+        .context("fetching tx")?
+        .ok_or(InvalidSubqueryReason::TxNotFound)?;
+    get_tx_field_value_from_tx(tx, &query)
+}
 
+/// Returns the value a tx subquery resolves to, given the transaction it refers to
+pub fn get_tx_field_value_from_tx(tx: Transaction, query: &TxSubquery) -> Result<H256> {
     if query.field_or_calldata_idx < TX_CALLDATA_IDX_OFFSET.try_into().unwrap() {
-        let tx_field_idx =
-            TxField::from_u32(query.field_or_calldata_idx).expect("Invalid field index");
+        let tx_field_idx = TxField::from_u32(query.field_or_calldata_idx).ok_or(
+            InvalidSubqueryReason::InvalidFieldIdx(query.field_or_calldata_idx),
+        )?;
 
-        let val = match tx_field_idx {
-            TxField::ChainId => H256::from_uint(&tx.chain_id.unwrap()),
-            TxField::Nonce => H256::from_uint(&tx.nonce),
-            TxField::MaxPriorityFeePerGas => H256::from_uint(&tx.max_priority_fee_per_gas.unwrap()),
-            TxField::MaxFeePerGas => H256::from_uint(&tx.max_fee_per_gas.unwrap()),
-            TxField::GasLimit => H256::from_uint(&tx.gas),
-            TxField::To => H256::from(tx.to.unwrap()),
-            TxField::Value => H256::from_uint(&tx.value),
-            TxField::Data => {
-                let padded = pad_to_bytes32(&tx.input);
-                H256::from(padded)
-            }
-            TxField::GasPrice => {
-                if tx.transaction_type.unwrap() == 2.into() {
-                    bail!("Gas Price not available for EIP-1559 transactions")
+        let val =
+            match tx_field_idx {
+                TxField::ChainId => {
+                    let chain_id = tx
+                        .chain_id
+                        .ok_or(InvalidSubqueryReason::FieldNotAvailable("chainId"))?;
+                    H256::from_uint(&chain_id)
+                }
+                TxField::Nonce => H256::from_uint(&tx.nonce),
+                TxField::MaxPriorityFeePerGas => {
+                    let fee = tx.max_priority_fee_per_gas.ok_or(
+                        InvalidSubqueryReason::FieldNotAvailable("maxPriorityFeePerGas"),
+                    )?;
+                    H256::from_uint(&fee)
+                }
+                TxField::MaxFeePerGas => {
+                    let fee = tx
+                        .max_fee_per_gas
+                        .ok_or(InvalidSubqueryReason::FieldNotAvailable("maxFeePerGas"))?;
+                    H256::from_uint(&fee)
                 }
-                let gas_price = tx.gas_price.unwrap();
-                H256::from_uint(&gas_price)
-            }
-            TxField::V => H256::from_low_u64_be(tx.v.as_u64()),
-            TxField::R => H256::from_uint(&tx.r),
-            TxField::S => H256::from_uint(&tx.s),
-            TxField::TxType => H256::from_low_u64_be(tx.transaction_type.unwrap().as_u64()),
-            TxField::BlockNumber => H256::from_low_u64_be(tx.block_number.unwrap().as_u64()),
-            TxField::TxIndex => H256::from_low_u64_be(tx.transaction_index.unwrap().as_u64()),
-            TxField::FunctionSelector => {
-                let calldata = tx.input;
-                let to = tx.to;
+                TxField::GasLimit => H256::from_uint(&tx.gas),
+                // contract creations have no recipient
+                TxField::To => tx.to.map(H256::from).unwrap_or_default(),
+                TxField::Value => H256::from_uint(&tx.value),
+                TxField::Data => {
+                    let padded = pad_to_bytes32(&tx.input);
+                    H256::from(padded)
+                }
+                TxField::GasPrice => {
+                    if tx.transaction_type.unwrap_or_default() >= 2.into() {
+                        bail!(InvalidSubqueryReason::GasPriceNotAvailable)
+                    }
+                    let gas_price = tx
+                        .gas_price
+                        .ok_or(InvalidSubqueryReason::FieldNotAvailable("gasPrice"))?;
+                    H256::from_uint(&gas_price)
+                }
+                TxField::V => H256::from_low_u64_be(tx.v.as_u64()),
+                TxField::R => H256::from_uint(&tx.r),
+                TxField::S => H256::from_uint(&tx.s),
+                TxField::TxType => {
+                    H256::from_low_u64_be(tx.transaction_type.unwrap_or_default().as_u64())
+                }
+                TxField::BlockNumber => H256::from_low_u64_be(tx.block_number.unwrap().as_u64()),
+                TxField::TxIndex => H256::from_low_u64_be(tx.transaction_index.unwrap().as_u64()),
+                TxField::FunctionSelector => {
+                    let calldata = tx.input;
+                    let to = tx.to;
 
-                if calldata.len() == 0 {
-                    H256::from_low_u64_be(TX_NO_CALLDATA_SELECTOR_VALUE as u64)
-                } else if calldata.len() > 0 && to.is_none() {
-                    H256::from_low_u64_be(TX_CONTRACT_DEPLOY_SELECTOR_VALUE as u64)
-                } else {
-                    if calldata.len() < 4 {
-                        bail!("Invalid calldata")
+                    if calldata.len() == 0 {
+                        H256::from_low_u64_be(TX_NO_CALLDATA_SELECTOR_VALUE as u64)
+                    } else if calldata.len() > 0 && to.is_none() {
+                        H256::from_low_u64_be(TX_CONTRACT_DEPLOY_SELECTOR_VALUE as u64)
+                    } else {
+                        if calldata.len() < 4 {
+                            bail!(InvalidSubqueryReason::InvalidCalldata)
+                        }
+                        let selector = &calldata[0..4];
+                        H256::from(pad_to_bytes32(selector))
                     }
-                    let selector = &calldata[0..4];
-                    H256::from(pad_to_bytes32(selector))
                 }
-            }
-            TxField::CalldataHash => {
-                let calldata = tx.input;
-                let hash = keccak256(&calldata);
-                H256::from(hash)
-            }
-            TxField::DataLength => H256::from_low_u64_be(tx.input.len() as u64),
-        };
+                TxField::CalldataHash => {
+                    let calldata = tx.input;
+                    let hash = keccak256(&calldata);
+                    H256::from(hash)
+                }
+                TxField::DataLength => H256::from_low_u64_be(tx.input.len() as u64),
+            };
         return Ok(val);
     }
 
@@ -121,8 +142,8 @@ pub async fn get_tx_field_value<P: JsonRpcClient>(
         let calldata = tx.input;
 
         let calldata_idx = (query.field_or_calldata_idx as usize) - TX_CALLDATA_IDX_OFFSET;
-        if calldata_idx >= (calldata.len() - 4) / 32 {
-            bail!("Invalid calldata index")
+        if calldata.len() < 4 || calldata_idx >= (calldata.len() - 4) / 32 {
+            bail!(InvalidSubqueryReason::InvalidCalldataIdx)
         }
         let calldata_bytes = &calldata[4 + calldata_idx * 32..4 + (calldata_idx + 1) * 32];
         Ok(H256::from_slice(calldata_bytes))
@@ -131,6 +152,9 @@ pub async fn get_tx_field_value<P: JsonRpcClient>(
         let contract_data_idx =
             (query.field_or_calldata_idx as usize) - TX_CONTRACT_DATA_IDX_OFFSET;
         let num_slots = usize::div_ceil(contract_data.len(), 32);
+        if contract_data_idx >= num_slots {
+            bail!(InvalidSubqueryReason::InvalidContractDataIdx)
+        }
         if contract_data_idx == num_slots - 1 {
             let contract_data_bytes = &contract_data[contract_data_idx * 32..];
             let padded = pad_to_bytes32(contract_data_bytes);
@@ -143,10 +167,8 @@ pub async fn get_tx_field_value<P: JsonRpcClient>(
 }
 
 impl<F: Field> FetchSubquery<F> for AssignedTxSubquery<F> {
-    fn fetch<P: JsonRpcClient>(&self, p: &Provider<P>) -> Result<H256> {
-        let rt = Runtime::new()?;
-        let val = rt.block_on(get_tx_field_value(p, (*self).into()))?;
-        Ok(val)
+    fn fetch<P: SubqueryDataSource>(&self, p: &P) -> Result<H256> {
+        p.get_tx_field_value((*self).into())
     }
 
     fn any_subquery(&self) -> AnySubquery {
@@ -4,7 +4,7 @@ use axiom_codec::{
         field_elements::FieldSubqueryResult,
         native::{
             AccountSubquery, AnySubquery, HeaderSubquery, ReceiptSubquery,
-            SolidityNestedMappingSubquery, StorageSubquery, TxSubquery,
+            SolidityNestedMappingSubquery, StorageSubquery, SubqueryType, TxSubquery,
         },
     },
     utils::native::{decode_field_to_addr, decode_hilo_to_h256},
@@ -12,7 +12,9 @@ use axiom_codec::{
 };
 use axiom_query::axiom_eth::{halo2_base::AssignedValue, Field};
 use ethers::types::{BigEndianHash, H256};
-use serde::{Serialize, Serializer};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::utils::get_subquery_type_from_any_subquery;
 
 #[derive(Clone, Copy)]
 pub struct AssignedHeaderSubquery<F: Field> {
@@ -145,8 +147,9 @@ impl<F: Field> From<AssignedSolidityNestedMappingSubquery<F>> for SolidityNested
     }
 }
 
+/// The data of a subquery, serialized without its type
 #[derive(Debug, Clone)]
-pub(crate) struct RawSubquery(pub(crate) AnySubquery);
+pub struct RawSubquery(pub AnySubquery);
 
 impl Serialize for RawSubquery {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -165,6 +168,33 @@ impl Serialize for RawSubquery {
     }
 }
 
+/// Without the `type` field of [Subquery], the data is decoded as the first subquery type whose
+/// fields it has, trying the types with more fields first
+impl<'de> Deserialize<'de> for RawSubquery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        fn decode<T: for<'a> Deserialize<'a>>(data: &serde_json::Value) -> Option<T> {
+            serde_json::from_value(data.clone()).ok()
+        }
+
+        let data = serde_json::Value::deserialize(deserializer)?;
+        if data.as_str() == Some("Null") {
+            return Ok(Self(AnySubquery::Null));
+        }
+        decode(&data)
+            .map(AnySubquery::Receipt)
+            .or_else(|| decode(&data).map(AnySubquery::SolidityNestedMapping))
+            .or_else(|| decode(&data).map(AnySubquery::Transaction))
+            .or_else(|| decode(&data).map(AnySubquery::Account))
+            .or_else(|| decode(&data).map(AnySubquery::Storage))
+            .or_else(|| decode(&data).map(AnySubquery::Header))
+            .map(Self)
+            .ok_or_else(|| D::Error::custom(format!("unknown subquery data {data}")))
+    }
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct Subquery {
     #[serde(rename = "subqueryData")]
@@ -174,6 +204,67 @@ pub struct Subquery {
     pub(crate) val: H256,
 }
 
+impl Subquery {
+    pub fn new(subquery: AnySubquery, val: H256) -> Self {
+        Self {
+            subquery_type: get_subquery_type_from_any_subquery(&subquery),
+            subquery_data: RawSubquery(subquery),
+            val,
+        }
+    }
+
+    pub fn subquery(&self) -> &AnySubquery {
+        &self.subquery_data.0
+    }
+
+    pub fn subquery_type(&self) -> u64 {
+        self.subquery_type
+    }
+
+    pub fn value(&self) -> H256 {
+        self.val
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SerializedSubquery {
+    subquery_data: serde_json::Value,
+    #[serde(rename = "type")]
+    subquery_type: u64,
+    val: H256,
+}
+
+/// Subquery data carries no tag of its own, so it is decoded according to the `type` field
+impl<'de> Deserialize<'de> for Subquery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        fn decode<T: for<'a> Deserialize<'a>, E: serde::de::Error>(
+            data: serde_json::Value,
+        ) -> Result<T, E> {
+            serde_json::from_value(data).map_err(E::custom)
+        }
+
+        let serialized = SerializedSubquery::deserialize(deserializer)?;
+        let data = serialized.subquery_data;
+        let subquery = match serialized.subquery_type {
+            x if x == SubqueryType::Null as u64 => AnySubquery::Null,
+            x if x == SubqueryType::Header as u64 => AnySubquery::Header(decode(data)?),
+            x if x == SubqueryType::Account as u64 => AnySubquery::Account(decode(data)?),
+            x if x == SubqueryType::Storage as u64 => AnySubquery::Storage(decode(data)?),
+            x if x == SubqueryType::Transaction as u64 => AnySubquery::Transaction(decode(data)?),
+            x if x == SubqueryType::Receipt as u64 => AnySubquery::Receipt(decode(data)?),
+            x if x == SubqueryType::SolidityNestedMapping as u64 => {
+                AnySubquery::SolidityNestedMapping(decode(data)?)
+            }
+            x => return Err(D::Error::custom(format!("unknown subquery type {x}"))),
+        };
+        Ok(Self::new(subquery, serialized.val))
+    }
+}
+
 impl From<Subquery> for AnySubquery {
     fn from(subquery: Subquery) -> Self {
         subquery.subquery_data.0
@@ -19,3 +19,15 @@ pub fn get_subquery_type_from_any_subquery(any_subquery: &AnySubquery) -> u64 {
     };
     subquery_type as u64
 }
+
+pub fn get_subquery_type_name(any_subquery: &AnySubquery) -> &'static str {
+    match any_subquery {
+        AnySubquery::Null => "null",
+        AnySubquery::Header(_) => "header",
+        AnySubquery::Account(_) => "account",
+        AnySubquery::Storage(_) => "storage",
+        AnySubquery::Receipt(_) => "receipt",
+        AnySubquery::Transaction(_) => "transaction",
+        AnySubquery::SolidityNestedMapping(_) => "solidity nested mapping",
+    }
+}
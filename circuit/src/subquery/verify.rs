@@ -0,0 +1,603 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{anyhow, bail, Context, Result};
+use axiom_codec::types::native::{
+    AccountSubquery, AnySubquery, HeaderSubquery, ReceiptSubquery, StorageSubquery, TxSubquery,
+};
+use axiom_query::axiom_eth::providers::{
+    account::get_acct_rlp,
+    receipt::{
+        construct_rc_tries_from_full_blocks, get_block_with_receipts, BlockWithReceipts,
+        TransactionReceipt as BlockReceipt,
+    },
+    transaction::{construct_tx_tries_from_full_blocks, BlockWithTransactions},
+};
+use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+use ethers::{
+    providers::{JsonRpcClient, Middleware, Provider},
+    types::{
+        Address, BigEndianHash, Block, BlockId, Bytes, EIP1186ProofResponse, Log, StorageProof,
+        Transaction, TransactionReceipt, H256, U64,
+    },
+    utils::{
+        keccak256,
+        rlp::{self, RlpStream},
+    },
+};
+use futures::{stream, StreamExt};
+use hasher::HasherKeccak;
+use num_traits::FromPrimitive;
+use tokio::runtime::Runtime;
+
+use super::{
+    account::{get_nonexistent_account_field_value, AccountField},
+    error::InvalidSubqueryReason,
+    header::get_header_field_value_from_block,
+    mapping::get_solidity_nested_mapping_storage_subquery,
+    receipt::get_receipt_field_value_from_receipt,
+    source::{get_subquery_field_value, SubqueryDataSource},
+    tx::get_tx_field_value_from_tx,
+};
+
+/// The `max_data_byte_len` of the production AxiomV2 transaction subquery circuit
+pub const TX_MAX_DATA_BYTE_LEN: usize = 8192;
+/// The `max_access_list_len` of the production AxiomV2 transaction subquery circuit
+pub const TX_MAX_ACCESS_LIST_LEN: usize = 4096;
+/// The `max_data_byte_len` of the production AxiomV2 receipt subquery circuit
+pub const RECEIPT_MAX_DATA_BYTE_LEN: usize = 800;
+/// The `max_log_num` of the production AxiomV2 receipt subquery circuit
+pub const RECEIPT_MAX_LOG_NUM: usize = 20;
+
+/// The largest transactions and receipts that Axiom can prove
+///
+/// The defaults match the limits of the production Axiom V2 subquery circuits.
+#[derive(Clone, Debug)]
+pub struct TxReceiptLimits {
+    /// Whether legacy, EIP-2930 and EIP-1559 transactions are supported, in that order.
+    ///
+    /// The AxiomV2 transaction circuit only supports these types, so EIP-4844 blob transactions
+    /// are always rejected.
+    pub tx_enabled_types: [bool; 3],
+    pub tx_max_data_byte_len: usize,
+    /// The maximum length of the RLP-encoded access list
+    pub tx_max_access_list_len: usize,
+    /// The maximum length of the data of each log
+    pub receipt_max_data_byte_len: usize,
+    pub receipt_max_log_num: usize,
+}
+
+impl Default for TxReceiptLimits {
+    fn default() -> Self {
+        Self {
+            tx_enabled_types: [true; 3],
+            tx_max_data_byte_len: TX_MAX_DATA_BYTE_LEN,
+            tx_max_access_list_len: TX_MAX_ACCESS_LIST_LEN,
+            receipt_max_data_byte_len: RECEIPT_MAX_DATA_BYTE_LEN,
+            receipt_max_log_num: RECEIPT_MAX_LOG_NUM,
+        }
+    }
+}
+
+impl TxReceiptLimits {
+    /// Checks that `tx` is of a supported type and within the size limits
+    pub fn check_tx(&self, tx: &Transaction) -> Result<()> {
+        let tx_type = tx.transaction_type.unwrap_or_default().as_u64();
+        if !self
+            .tx_enabled_types
+            .get(tx_type as usize)
+            .copied()
+            .unwrap_or(false)
+        {
+            bail!(InvalidSubqueryReason::UnsupportedTxType(tx_type));
+        }
+        if tx.input.len() > self.tx_max_data_byte_len {
+            bail!(InvalidSubqueryReason::TxDataTooLong {
+                len: tx.input.len(),
+                max: self.tx_max_data_byte_len,
+            });
+        }
+        if let Some(access_list) = &tx.access_list {
+            let len = rlp::encode(access_list).len();
+            if len > self.tx_max_access_list_len {
+                bail!(InvalidSubqueryReason::AccessListTooLong {
+                    len,
+                    max: self.tx_max_access_list_len,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a receipt with `logs` is within the size limits
+    pub fn check_receipt_logs(&self, logs: &[Log]) -> Result<()> {
+        if logs.len() > self.receipt_max_log_num {
+            bail!(InvalidSubqueryReason::TooManyLogs {
+                num: logs.len(),
+                max: self.receipt_max_log_num,
+            });
+        }
+        if let Some(log) = logs
+            .iter()
+            .find(|log| log.data.len() > self.receipt_max_data_byte_len)
+        {
+            bail!(InvalidSubqueryReason::LogDataTooLong {
+                len: log.data.len(),
+                max: self.receipt_max_data_byte_len,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Verifies a Merkle-Patricia proof of `key` against `root` and returns the value it proves,
+/// or `None` if it proves that `key` is not in the trie
+pub fn verify_mpt_proof(root: H256, key: &[u8], proof: &[Bytes]) -> Result<Option<Vec<u8>>> {
+    let trie = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), Arc::new(HasherKeccak::new()));
+    let proof = proof.iter().map(|node| node.to_vec()).collect();
+    trie.verify_proof(root.as_bytes(), key, proof)
+        .map_err(|e| anyhow!("Invalid MPT proof against root {root:?}: {e}"))
+}
+
+/// Verifies an `eth_getProof` account proof against `state_root`
+///
+/// Returns whether the proof shows that the account exists.
+pub fn verify_account_proof(state_root: H256, proof: &EIP1186ProofResponse) -> Result<bool> {
+    let key = keccak256(proof.address);
+    match verify_mpt_proof(state_root, &key, &proof.account_proof)? {
+        Some(value) if value == get_acct_rlp(proof) => Ok(true),
+        Some(_) => bail!(
+            "Account proof of {:?} does not match the returned account state",
+            proof.address
+        ),
+        None => Ok(false),
+    }
+}
+
+/// Verifies an `eth_getProof` storage proof against the `storage_hash` of its account
+pub fn verify_storage_proof(storage_hash: H256, proof: &StorageProof) -> Result<()> {
+    let key = keccak256(H256::from_uint(&proof.key));
+    let value = verify_mpt_proof(storage_hash, &key, &proof.proof)?;
+    // zero slots are not stored in the trie
+    let expected = (!proof.value.is_zero()).then(|| rlp::encode(&proof.value).to_vec());
+    if value != expected {
+        bail!(
+            "Storage proof of slot {:?} does not match the returned value {:?}",
+            proof.key,
+            proof.value
+        );
+    }
+    Ok(())
+}
+
+/// RLP-encodes the header of `block`, with the fields of the forks it comes after
+pub fn get_header_rlp<TX>(block: &Block<TX>) -> Result<Vec<u8>> {
+    let missing = |field| anyhow!("Block is missing its {field}");
+    let mut stream = RlpStream::new();
+    stream.begin_unbounded_list();
+    stream
+        .append(&block.parent_hash)
+        .append(&block.uncles_hash)
+        .append(&block.author.ok_or_else(|| missing("miner"))?)
+        .append(&block.state_root)
+        .append(&block.transactions_root)
+        .append(&block.receipts_root)
+        .append(&block.logs_bloom.ok_or_else(|| missing("logsBloom"))?)
+        .append(&block.difficulty)
+        .append(&block.number.ok_or_else(|| missing("number"))?)
+        .append(&block.gas_limit)
+        .append(&block.gas_used)
+        .append(&block.timestamp)
+        .append(&block.extra_data)
+        .append(&block.mix_hash.ok_or_else(|| missing("mixHash"))?)
+        .append(&block.nonce.ok_or_else(|| missing("nonce"))?);
+    // each fork appends its fields after those of the forks before it
+    if let Some(base_fee_per_gas) = block.base_fee_per_gas {
+        stream.append(&base_fee_per_gas);
+    }
+    if let Some(withdrawals_root) = block.withdrawals_root {
+        stream.append(&withdrawals_root);
+    }
+    if let Some(blob_gas_used) = block.blob_gas_used {
+        stream.append(&blob_gas_used);
+    }
+    if let Some(excess_blob_gas) = block.excess_blob_gas {
+        stream.append(&excess_blob_gas);
+    }
+    if let Some(parent_beacon_block_root) = block.parent_beacon_block_root {
+        stream.append(&parent_beacon_block_root);
+    }
+    stream.finalize_unbounded_list();
+    Ok(stream.out().to_vec())
+}
+
+/// Checks that the header of `block` hashes to `trusted_hash` and returns its hash
+///
+/// Without a trusted hash, the header is checked against the hash returned with it. This only
+/// catches a provider whose header is inconsistent with its hash, and not one that returns a
+/// different block altogether.
+pub fn verify_block_hash<TX>(block: &Block<TX>, trusted_hash: Option<H256>) -> Result<H256> {
+    let hash = H256(keccak256(get_header_rlp(block)?));
+    let expected = trusted_hash
+        .or(block.hash)
+        .ok_or_else(|| anyhow!("Block is missing its hash"))?;
+    if hash != expected {
+        bail!(
+            "Header of block {:?} hashes to {hash:?} instead of {expected:?}",
+            block.number.unwrap_or_default()
+        );
+    }
+    Ok(hash)
+}
+
+/// Fetches block `block_number` and checks its header against `trusted_hash` with
+/// [verify_block_hash]
+pub async fn get_verified_block<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    block_number: u32,
+    trusted_hash: Option<H256>,
+) -> Result<Block<H256>> {
+    let mut block = provider
+        .get_block(block_number as u64)
+        .await?
+        .ok_or(InvalidSubqueryReason::BlockNotFound)?;
+    block.hash = Some(verify_block_hash(&block, trusted_hash)?);
+    Ok(block)
+}
+
+/// Like [get_header_field_value](super::header::get_header_field_value), but reads the field
+/// from a header checked with [verify_block_hash]
+pub async fn get_verified_header_field_value<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    query: HeaderSubquery,
+    trusted_hash: Option<H256>,
+) -> Result<H256> {
+    let block = get_verified_block(provider, query.block_number, trusted_hash).await?;
+    get_header_field_value_from_block(block, &query)
+}
+
+/// Fetches the `eth_getProof` response for `addr` and `slots` and verifies the account proof
+/// against the state root of the block, whose header is checked with [verify_block_hash]
+///
+/// Returns `None` if the proof shows that the account does not exist.
+pub async fn get_verified_account_proof<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    block_number: u32,
+    addr: Address,
+    slots: Vec<H256>,
+    trusted_hash: Option<H256>,
+) -> Result<Option<EIP1186ProofResponse>> {
+    let block_id = BlockId::from(block_number as u64);
+    let block = get_verified_block(provider, block_number, trusted_hash).await?;
+    let proof = provider.get_proof(addr, slots, Some(block_id)).await?;
+    if proof.address != addr {
+        bail!(
+            "Provider returned a proof for {:?} instead of {addr:?}",
+            proof.address
+        );
+    }
+    let exists = verify_account_proof(block.state_root, &proof)?;
+    Ok(exists.then_some(proof))
+}
+
+/// Like [get_account_field_value](super::account::get_account_field_value), but reads the
+/// account from a verified `eth_getProof` response
+pub async fn get_verified_account_field_value<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    query: AccountSubquery,
+    trusted_hash: Option<H256>,
+) -> Result<H256> {
+    let account_field = AccountField::from_u32(query.field_idx)
+        .ok_or(InvalidSubqueryReason::InvalidFieldIdx(query.field_idx))?;
+    let proof = match get_verified_account_proof(
+        provider,
+        query.block_number,
+        query.addr,
+        vec![],
+        trusted_hash,
+    )
+    .await?
+    {
+        Some(proof) => proof,
+        None => return Ok(get_nonexistent_account_field_value(account_field)),
+    };
+    let val = match account_field {
+        AccountField::Nonce => H256::from_low_u64_be(proof.nonce.as_u64()),
+        AccountField::Balance => H256::from_uint(&proof.balance),
+        AccountField::StorageHash => proof.storage_hash,
+        AccountField::CodeHash => proof.code_hash,
+    };
+    Ok(val)
+}
+
+/// Like [get_storage_field_value](super::storage::get_storage_field_value), but reads the slot
+/// from a verified `eth_getProof` response
+pub async fn get_verified_storage_field_value<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    query: StorageSubquery,
+    trusted_hash: Option<H256>,
+) -> Result<H256> {
+    let slot = H256::from_uint(&query.slot);
+    let proof = match get_verified_account_proof(
+        provider,
+        query.block_number,
+        query.addr,
+        vec![slot],
+        trusted_hash,
+    )
+    .await?
+    {
+        Some(proof) => proof,
+        None => return Ok(H256::zero()),
+    };
+    let storage_proof = proof
+        .storage_proof
+        .iter()
+        .find(|storage_proof| storage_proof.key == query.slot)
+        .ok_or_else(|| anyhow!("Provider did not return a storage proof for slot {slot:?}"))?;
+    verify_storage_proof(proof.storage_hash, storage_proof)?;
+    Ok(H256::from_uint(&storage_proof.value))
+}
+
+/// Rebuilds the transaction trie of `block` and checks it against its `transactionsRoot`
+pub fn verify_transactions_root(block: BlockWithTransactions) -> Result<()> {
+    let number = block.number;
+    construct_tx_tries_from_full_blocks(vec![block])
+        .with_context(|| format!("Transactions of block {number} do not match its root"))?;
+    Ok(())
+}
+
+/// Rebuilds the receipt trie of `block` and checks it against its `receiptsRoot`
+pub fn verify_receipts_root(block: BlockWithReceipts) -> Result<()> {
+    let number = block.number;
+    construct_rc_tries_from_full_blocks(vec![block])
+        .with_context(|| format!("Receipts of block {number} do not match its root"))?;
+    Ok(())
+}
+
+/// Like [get_tx_field_value](super::tx::get_tx_field_value), but fetches every transaction of
+/// the block and checks them against its `transactionsRoot`, and the transaction against `limits`
+///
+/// The header of the block is checked with [verify_block_hash].
+pub async fn get_verified_tx_field_value<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    query: TxSubquery,
+    limits: &TxReceiptLimits,
+    trusted_hash: Option<H256>,
+) -> Result<H256> {
+    let block = provider
+        .get_block_with_txs(query.block_number as u64)
+        .await?
+        .ok_or(InvalidSubqueryReason::BlockNotFound)?;
+    verify_block_hash(&block, trusted_hash)?;
+    let block = BlockWithTransactions::try_from(block).map_err(|e| anyhow!(e))?;
+    let tx = block
+        .transactions
+        .get(query.tx_idx as usize)
+        .cloned()
+        .ok_or(InvalidSubqueryReason::TxNotFound)?;
+    verify_transactions_root(block)?;
+    limits.check_tx(&tx)?;
+    get_tx_field_value_from_tx(tx, &query)
+}
+
+/// Like [get_receipt_field_value](super::receipt::get_receipt_field_value), but fetches every
+/// receipt of the block and checks them against its `receiptsRoot`, and the receipt against
+/// `limits`
+///
+/// The `receiptsRoot` is taken from a header checked with [verify_block_hash].
+pub async fn get_verified_receipt_field_value<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    query: ReceiptSubquery,
+    limits: &TxReceiptLimits,
+    trusted_hash: Option<H256>,
+) -> Result<H256> {
+    let header = get_verified_block(provider, query.block_number, trusted_hash).await?;
+    let block = get_block_with_receipts(provider, query.block_number as u64, None).await?;
+    if block.receipts_root != header.receipts_root {
+        bail!(
+            "Receipts of block {} are for receipts root {:?} instead of {:?}",
+            query.block_number,
+            block.receipts_root,
+            header.receipts_root
+        );
+    }
+    let receipt = block
+        .receipts
+        .get(query.tx_idx as usize)
+        .cloned()
+        .ok_or(InvalidSubqueryReason::TxNotFound)?;
+    verify_receipts_root(block)?;
+    limits.check_receipt_logs(&receipt.logs)?;
+    get_receipt_field_value_from_receipt(to_ethers_receipt(receipt), &query)
+}
+
+/// Converts a receipt of a [BlockWithReceipts] to the `ethers` receipt, with the fields that
+/// receipt subqueries read
+fn to_ethers_receipt(receipt: BlockReceipt) -> TransactionReceipt {
+    TransactionReceipt {
+        transaction_index: U64::from(receipt.transaction_index.as_u64()),
+        block_number: receipt
+            .block_number
+            .map(|number| U64::from(number.as_u64())),
+        cumulative_gas_used: receipt.cumulative_gas_used,
+        logs: receipt.logs,
+        logs_bloom: receipt.logs_bloom,
+        root: receipt.state_root,
+        status: receipt.status_code.map(|status| U64::from(status.as_u64())),
+        transaction_type: Some(U64::from(receipt.transaction_type.as_u64())),
+        ..Default::default()
+    }
+}
+
+/// Fetches the result of any subquery over JSON-RPC and verifies it against the roots of its
+/// block
+///
+/// The header of a block in `trusted_hashes` is checked against the hash it maps to, and any
+/// other header against the hash returned with it.
+pub async fn get_verified_subquery_field_value<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    subquery: AnySubquery,
+    limits: &TxReceiptLimits,
+    trusted_hashes: &HashMap<u32, H256>,
+) -> Result<H256> {
+    let trusted_hash = |block_number: u32| trusted_hashes.get(&block_number).copied();
+    match subquery {
+        AnySubquery::Header(query) => {
+            let trusted_hash = trusted_hash(query.block_number);
+            get_verified_header_field_value(provider, query, trusted_hash).await
+        }
+        AnySubquery::Account(query) => {
+            let trusted_hash = trusted_hash(query.block_number);
+            get_verified_account_field_value(provider, query, trusted_hash).await
+        }
+        AnySubquery::Storage(query) => {
+            let trusted_hash = trusted_hash(query.block_number);
+            get_verified_storage_field_value(provider, query, trusted_hash).await
+        }
+        AnySubquery::Transaction(query) => {
+            let trusted_hash = trusted_hash(query.block_number);
+            get_verified_tx_field_value(provider, query, limits, trusted_hash).await
+        }
+        AnySubquery::Receipt(query) => {
+            let trusted_hash = trusted_hash(query.block_number);
+            get_verified_receipt_field_value(provider, query, limits, trusted_hash).await
+        }
+        AnySubquery::SolidityNestedMapping(query) => {
+            let storage_query = get_solidity_nested_mapping_storage_subquery(&query)?;
+            let trusted_hash = trusted_hash(storage_query.block_number);
+            get_verified_storage_field_value(provider, storage_query, trusted_hash).await
+        }
+        _ => get_subquery_field_value(provider, subquery).await,
+    }
+}
+
+/// Fetches subquery results over JSON-RPC and verifies them natively before they are used
+///
+/// Account and storage results are read from `eth_getProof` and checked against the state root
+/// of their block. Transaction and receipt results are checked by rebuilding the transaction and
+/// receipt tries of their block, which fetches every transaction or receipt in it, and must be
+/// within `limits`. An unprovable subquery is caught before proving.
+///
+/// The roots are read from the header of the block, which is checked to hash to the trusted hash
+/// of the block if one is set with [set_trusted_block_hashes](Self::set_trusted_block_hashes).
+/// Otherwise it is checked against the hash the provider returns with it, which only catches a
+/// provider whose responses are inconsistent with each other.
+#[derive(Clone, Debug)]
+pub struct ProofVerifyingProvider<P: JsonRpcClient> {
+    provider: Provider<P>,
+    limits: TxReceiptLimits,
+    trusted_block_hashes: HashMap<u32, H256>,
+}
+
+impl<P: JsonRpcClient> ProofVerifyingProvider<P> {
+    pub fn new(provider: Provider<P>) -> Self {
+        Self {
+            provider,
+            limits: Default::default(),
+            trusted_block_hashes: HashMap::new(),
+        }
+    }
+
+    /// Sets the block hashes, by block number, that headers are checked against
+    pub fn set_trusted_block_hashes(&mut self, trusted_block_hashes: HashMap<u32, H256>) {
+        self.trusted_block_hashes = trusted_block_hashes;
+    }
+
+    pub fn use_trusted_block_hashes(mut self, trusted_block_hashes: HashMap<u32, H256>) -> Self {
+        self.set_trusted_block_hashes(trusted_block_hashes);
+        self
+    }
+
+    fn trusted_hash(&self, block_number: u32) -> Option<H256> {
+        self.trusted_block_hashes.get(&block_number).copied()
+    }
+
+    pub fn set_limits(&mut self, limits: TxReceiptLimits) {
+        self.limits = limits;
+    }
+
+    pub fn use_limits(mut self, limits: TxReceiptLimits) -> Self {
+        self.set_limits(limits);
+        self
+    }
+}
+
+impl<P: JsonRpcClient> SubqueryDataSource for ProofVerifyingProvider<P> {
+    fn get_header_field_value(&self, query: HeaderSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        let trusted_hash = self.trusted_hash(query.block_number);
+        rt.block_on(get_verified_header_field_value(
+            &self.provider,
+            query,
+            trusted_hash,
+        ))
+    }
+
+    fn get_account_field_value(&self, query: AccountSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        let trusted_hash = self.trusted_hash(query.block_number);
+        rt.block_on(get_verified_account_field_value(
+            &self.provider,
+            query,
+            trusted_hash,
+        ))
+    }
+
+    fn get_storage_field_value(&self, query: StorageSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        let trusted_hash = self.trusted_hash(query.block_number);
+        rt.block_on(get_verified_storage_field_value(
+            &self.provider,
+            query,
+            trusted_hash,
+        ))
+    }
+
+    fn get_tx_field_value(&self, query: TxSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        let trusted_hash = self.trusted_hash(query.block_number);
+        rt.block_on(get_verified_tx_field_value(
+            &self.provider,
+            query,
+            &self.limits,
+            trusted_hash,
+        ))
+    }
+
+    fn get_receipt_field_value(&self, query: ReceiptSubquery) -> Result<H256> {
+        let rt = Runtime::new()?;
+        let trusted_hash = self.trusted_hash(query.block_number);
+        rt.block_on(get_verified_receipt_field_value(
+            &self.provider,
+            query,
+            &self.limits,
+            trusted_hash,
+        ))
+    }
+
+    /// Fetches and verifies all subqueries concurrently on a single runtime
+    fn fetch_many(&self, subqueries: Vec<AnySubquery>, parallelism: usize) -> Vec<Result<H256>> {
+        let rt = match Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                return subqueries
+                    .iter()
+                    .map(|_| Err(anyhow!("Failed to start runtime: {e}")))
+                    .collect()
+            }
+        };
+        rt.block_on(
+            stream::iter(subqueries)
+                .map(|subquery| {
+                    get_verified_subquery_field_value(
+                        &self.provider,
+                        subquery,
+                        &self.limits,
+                        &self.trusted_block_hashes,
+                    )
+                })
+                .buffered(parallelism.max(1))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,490 @@
+use std::{
+    fs,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::Result;
+use axiom_codec::{
+    types::native::{
+        AccountSubquery, AnySubquery, HeaderSubquery, ReceiptSubquery, StorageSubquery, TxSubquery,
+    },
+    HiLo,
+};
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{circuit::BaseCircuitParams, RangeChip},
+        AssignedValue,
+    },
+    halo2curves::bn256::Fr,
+    rlc::circuit::builder::RlcCircuitBuilder,
+};
+use ethers::types::{BigEndianHash, H256, U256};
+
+use super::utils::{all_subqueries_call, EmptyCircuitInput};
+use crate::{
+    run::inner::mock,
+    scaffold::{AxiomCircuit, AxiomCircuitScaffold, LimitError},
+    subquery::{
+        cache::{CachedDataSource, SubqueryCacheMode},
+        caller::{SubqueryCaller, SubqueryConfig},
+        error::{InvalidSubqueryReason, SubqueryError, SubqueryErrors},
+        mock::{MockFixture, MockStrategy},
+        source::{
+            InMemoryDataSource, JsonFixtureDataSource, SubqueryDataSource, SubqueryFixtureEntry,
+        },
+        types::{AssignedAccountSubquery, AssignedHeaderSubquery, RawSubquery},
+        AccountField, HeaderField,
+    },
+    types::{AxiomCircuitParams, AxiomV2DataAndResults, AxiomV2RunOutput},
+    utils::{build_axiom_v2_query_data, build_offchain_compute_query},
+};
+
+#[derive(Debug, Clone, Default)]
+struct AllSubqueryTest;
+
+impl<P: SubqueryDataSource> AxiomCircuitScaffold<P, Fr> for AllSubqueryTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        _range: &RangeChip<Fr>,
+        subquery_caller: Arc<Mutex<SubqueryCaller<P, Fr>>>,
+        _callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) {
+        all_subqueries_call(builder, subquery_caller);
+    }
+}
+
+fn get_base_test_params() -> AxiomCircuitParams {
+    let params = BaseCircuitParams {
+        k: 12,
+        num_advice_per_phase: vec![4],
+        num_lookup_advice_per_phase: vec![1],
+        num_fixed: 1,
+        num_instance_columns: 1,
+        lookup_bits: Some(11),
+    };
+    AxiomCircuitParams::Base(params)
+}
+
+/// Returns fixture entries with a distinct non-zero value for every subquery made by [AllSubqueryTest]
+fn get_fixture_entries() -> Vec<SubqueryFixtureEntry> {
+    let params = get_base_test_params();
+    // without inputs every subquery is mocked, so the data source is never queried
+    let runner = AxiomCircuit::<_, _, AllSubqueryTest>::new(InMemoryDataSource::new(), params);
+    runner
+        .scaffold_output()
+        .data_query
+        .into_iter()
+        .enumerate()
+        .map(|(i, subquery)| SubqueryFixtureEntry {
+            subquery: subquery.into(),
+            value: H256::from_low_u64_be(i as u64 + 1),
+        })
+        .collect()
+}
+
+#[test]
+pub fn test_in_memory_data_source() {
+    let params = get_base_test_params();
+    let entries = get_fixture_entries();
+    let data_source: InMemoryDataSource = entries.clone().into_iter().collect();
+    let runner = AxiomCircuit::<_, _, AllSubqueryTest>::new(data_source.clone(), params.clone())
+        .use_inputs(Some(Default::default()));
+    let results = runner.scaffold_output();
+    assert_eq!(results.data_query.len(), entries.len());
+    for (subquery, entry) in results.data_query.into_iter().zip(entries) {
+        assert_eq!(subquery.val, entry.value);
+    }
+    mock::<_, AllSubqueryTest>(
+        data_source,
+        params,
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+}
+
+#[test]
+pub fn test_json_fixture_data_source() {
+    let params = get_base_test_params();
+    let entries = get_fixture_entries();
+    let json = serde_json::to_string(&entries).unwrap();
+    let data_source = JsonFixtureDataSource::from_json(&json).unwrap();
+    for entry in entries {
+        assert_eq!(data_source.fetch(entry.subquery).unwrap(), entry.value);
+    }
+    mock::<_, AllSubqueryTest>(
+        data_source,
+        params,
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+}
+
+#[test]
+pub fn test_missing_subquery() {
+    let data_source = InMemoryDataSource::new();
+    let subquery = AnySubquery::Header(HeaderSubquery {
+        block_number: 9730000,
+        field_idx: 0,
+    });
+    assert!(data_source.fetch(subquery).is_err());
+}
+
+#[test]
+pub fn test_subquery_errors() {
+    let params = get_base_test_params();
+    let entries = get_fixture_entries();
+    let data_source: InMemoryDataSource = entries.clone().into_iter().skip(1).collect();
+    let runner = AxiomCircuit::<_, _, AllSubqueryTest>::new(data_source.clone(), params.clone())
+        .use_inputs(Some(Default::default()));
+    let errors = runner.subquery_errors();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], SubqueryError::DataSource { .. }));
+    assert_eq!(errors[0].subquery(), &entries[0].subquery);
+
+    let err = mock::<_, AllSubqueryTest>(
+        data_source,
+        params,
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.downcast_ref::<SubqueryErrors>().unwrap().0.len(), 1);
+
+    let err = SubqueryError::new(
+        entries[0].subquery.clone(),
+        anyhow::anyhow!(InvalidSubqueryReason::LogNotFound),
+    );
+    assert!(matches!(
+        err,
+        SubqueryError::Invalid {
+            reason: InvalidSubqueryReason::LogNotFound,
+            ..
+        }
+    ));
+}
+
+#[test]
+pub fn test_cached_data_source() {
+    let cache_dir =
+        std::env::temp_dir().join(format!("axiom-subquery-cache-{}", std::process::id()));
+    let entries = get_fixture_entries();
+    let data_source: InMemoryDataSource = entries.clone().into_iter().collect();
+
+    let cached = CachedDataSource::new(data_source, &cache_dir, SubqueryCacheMode::Use);
+    for entry in entries.iter() {
+        assert_eq!(cached.fetch(entry.subquery.clone()).unwrap(), entry.value);
+        assert!(cached.cache_path(&entry.subquery).exists());
+    }
+
+    // cached results are served without consulting the inner data source
+    let cached = CachedDataSource::new(
+        InMemoryDataSource::new(),
+        &cache_dir,
+        SubqueryCacheMode::Use,
+    );
+    for entry in entries.iter() {
+        assert_eq!(cached.fetch(entry.subquery.clone()).unwrap(), entry.value);
+    }
+    mock::<_, AllSubqueryTest>(
+        cached,
+        get_base_test_params(),
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+
+    for mode in [SubqueryCacheMode::Refresh, SubqueryCacheMode::Bypass] {
+        let cached = CachedDataSource::new(InMemoryDataSource::new(), &cache_dir, mode);
+        assert!(cached.fetch(entries[0].subquery.clone()).is_err());
+    }
+
+    fs::remove_dir_all(cache_dir).unwrap();
+}
+
+/// Counts the subqueries that are fetched one at a time rather than through `fetch_many`
+#[derive(Clone, Debug)]
+struct CountingDataSource {
+    inner: InMemoryDataSource,
+    single_fetches: Arc<AtomicUsize>,
+}
+
+impl CountingDataSource {
+    fn count<T>(&self, result: T) -> T {
+        self.single_fetches.fetch_add(1, Ordering::SeqCst);
+        result
+    }
+}
+
+impl SubqueryDataSource for CountingDataSource {
+    fn get_header_field_value(&self, query: HeaderSubquery) -> Result<H256> {
+        self.count(self.inner.get_header_field_value(query))
+    }
+
+    fn get_account_field_value(&self, query: AccountSubquery) -> Result<H256> {
+        self.count(self.inner.get_account_field_value(query))
+    }
+
+    fn get_storage_field_value(&self, query: StorageSubquery) -> Result<H256> {
+        self.count(self.inner.get_storage_field_value(query))
+    }
+
+    fn get_tx_field_value(&self, query: TxSubquery) -> Result<H256> {
+        self.count(self.inner.get_tx_field_value(query))
+    }
+
+    fn get_receipt_field_value(&self, query: ReceiptSubquery) -> Result<H256> {
+        self.count(self.inner.get_receipt_field_value(query))
+    }
+
+    fn fetch_many(&self, subqueries: Vec<AnySubquery>, parallelism: usize) -> Vec<Result<H256>> {
+        self.inner.fetch_many(subqueries, parallelism)
+    }
+}
+
+#[test]
+pub fn test_prefetch_subqueries() {
+    let params = get_base_test_params();
+    let entries = get_fixture_entries();
+    let data_source = CountingDataSource {
+        inner: entries.clone().into_iter().collect(),
+        single_fetches: Default::default(),
+    };
+    let subquery_config = SubqueryConfig {
+        prefetch_parallelism: Some(4),
+        ..Default::default()
+    };
+    let runner = AxiomCircuit::<_, _, AllSubqueryTest>::new(data_source.clone(), params.clone())
+        .use_inputs(Some(Default::default()))
+        .use_subquery_config(subquery_config.clone());
+    let results = runner.scaffold_output();
+    for (subquery, entry) in results.data_query.into_iter().zip(entries) {
+        assert_eq!(subquery.val, entry.value);
+    }
+    mock::<_, AllSubqueryTest>(
+        data_source.clone(),
+        params,
+        Some(Default::default()),
+        subquery_config,
+    )
+    .unwrap();
+    assert_eq!(data_source.single_fetches.load(Ordering::SeqCst), 0);
+}
+
+#[derive(Debug, Clone, Default)]
+struct DuplicateSubqueryTest;
+
+impl<P: SubqueryDataSource> AxiomCircuitScaffold<P, Fr> for DuplicateSubqueryTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        _range: &RangeChip<Fr>,
+        subquery_caller: Arc<Mutex<SubqueryCaller<P, Fr>>>,
+        callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) {
+        let ctx = builder.base.main(0);
+        let mut subquery_caller = subquery_caller.lock().unwrap();
+        let block_number = ctx.load_witness(Fr::from(9730000));
+        let header = AssignedHeaderSubquery {
+            block_number,
+            field_idx: ctx.load_constant(Fr::from(HeaderField::GasLimit)),
+        };
+        for _ in 0..2 {
+            callback.push(subquery_caller.call(ctx, header));
+            // each call assigns its own constants, the repeated call is merged by its values
+            let account = AssignedAccountSubquery {
+                block_number,
+                field_idx: ctx.load_constant(Fr::from(AccountField::Balance)),
+                addr: ctx.load_constant(Fr::zero()),
+            };
+            subquery_caller.call(ctx, account);
+        }
+    }
+}
+
+#[test]
+pub fn test_subquery_dedup() {
+    let params = get_base_test_params();
+    let runner =
+        AxiomCircuit::<_, _, DuplicateSubqueryTest>::new(InMemoryDataSource::new(), params.clone());
+    assert_eq!(runner.scaffold_output().data_query.len(), 4);
+
+    let subquery_config = SubqueryConfig {
+        dedup: true,
+        ..Default::default()
+    };
+    let runner =
+        AxiomCircuit::<_, _, DuplicateSubqueryTest>::new(InMemoryDataSource::new(), params.clone())
+            .use_inputs(Some(Default::default()))
+            .use_subquery_config(subquery_config.clone());
+    let data_query = runner.scaffold_output().data_query;
+    assert_eq!(data_query.len(), 2);
+
+    let data_source: InMemoryDataSource = data_query
+        .into_iter()
+        .enumerate()
+        .map(|(i, subquery)| SubqueryFixtureEntry {
+            subquery: subquery.into(),
+            value: H256::from_low_u64_be(i as u64 + 1),
+        })
+        .collect();
+    mock::<_, DuplicateSubqueryTest>(
+        data_source,
+        params,
+        Some(Default::default()),
+        subquery_config,
+    )
+    .unwrap();
+}
+
+#[test]
+pub fn test_subquery_and_output_limits() {
+    let params = get_base_test_params();
+    let runner =
+        AxiomCircuit::<_, _, DuplicateSubqueryTest>::new(InMemoryDataSource::new(), params.clone());
+    runner.check_limits().unwrap();
+
+    let runner =
+        AxiomCircuit::<_, _, DuplicateSubqueryTest>::new(InMemoryDataSource::new(), params)
+            .use_max_user_subqueries(3)
+            .use_max_user_outputs(1);
+    let errors = runner.limit_errors();
+    assert_eq!(
+        errors,
+        vec![
+            LimitError::Subqueries {
+                num: 4,
+                max: 3,
+                usage: vec![("account", 2), ("header", 2)],
+                region: None,
+            },
+            LimitError::Outputs {
+                num: 2,
+                max: 1,
+                region: None,
+            },
+        ]
+    );
+    assert_eq!(
+        errors[0].to_string(),
+        "subquery #4 exceeds limit of 3 (4 subqueries made: 2 account, 2 header)"
+    );
+    assert_eq!(
+        runner.check_limits().unwrap_err().to_string(),
+        errors[0].to_string()
+    );
+}
+
+#[test]
+pub fn test_mock_strategies() {
+    let nonce = AnySubquery::Account(AccountSubquery {
+        block_number: 9730000,
+        addr: Default::default(),
+        field_idx: AccountField::Nonce as u32,
+    });
+    let miner = AnySubquery::Header(HeaderSubquery {
+        block_number: 9730000,
+        field_idx: HeaderField::Miner as u32,
+    });
+    let random = MockStrategy::Random { seed: 1 };
+    for (subquery, bits) in [(&nonce, 64), (&miner, 160)] {
+        let value = random.mock_value(subquery);
+        assert_eq!(value, random.mock_value(subquery));
+        assert_ne!(value, MockStrategy::Random { seed: 2 }.mock_value(subquery));
+        assert!(!value.is_zero());
+        assert!(value.into_uint() < U256::one() << bits);
+    }
+
+    // subqueries that are not in the fixture take the result of another block for the same field
+    let fixture = MockFixture::from(vec![SubqueryFixtureEntry {
+        subquery: nonce.clone(),
+        value: H256::from_low_u64_be(5),
+    }]);
+    let other_block_nonce = AnySubquery::Account(AccountSubquery {
+        block_number: 1,
+        addr: Default::default(),
+        field_idx: AccountField::Nonce as u32,
+    });
+    assert_eq!(fixture.get(&nonce), H256::from_low_u64_be(5));
+    assert_eq!(fixture.get(&other_block_nonce), H256::from_low_u64_be(5));
+    assert_eq!(fixture.get(&miner), H256::zero());
+
+    // without inputs, every subquery is answered by the mock strategy
+    let runner = AxiomCircuit::<_, _, AllSubqueryTest>::new(
+        InMemoryDataSource::new(),
+        get_base_test_params(),
+    )
+    .use_subquery_config(SubqueryConfig {
+        mock_strategy: random.clone(),
+        ..Default::default()
+    });
+    for subquery in runner.scaffold_output().data_query {
+        let value = subquery.val;
+        assert_eq!(value, random.mock_value(&subquery.into()));
+    }
+}
+
+#[test]
+pub fn test_output_round_trip() {
+    let entries = get_fixture_entries();
+    let data_source: InMemoryDataSource = entries.clone().into_iter().collect();
+    let output = AxiomCircuit::<_, _, AllSubqueryTest>::new(data_source, get_base_test_params())
+        .use_inputs(Some(Default::default()))
+        .scaffold_output();
+    let json = serde_json::to_value(&output).unwrap();
+    let deserialized: AxiomV2DataAndResults = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&deserialized).unwrap(), json);
+    assert_eq!(deserialized.compute_results(), output.compute_results());
+    for (subquery, entry) in deserialized.data_query().iter().zip(entries) {
+        assert_eq!(subquery.subquery(), &entry.subquery);
+        assert_eq!(subquery.value(), entry.value);
+    }
+}
+
+#[test]
+pub fn test_run_output_round_trip() {
+    let entries = get_fixture_entries();
+    let data_source: InMemoryDataSource = entries.clone().into_iter().collect();
+    let data = AxiomCircuit::<_, _, AllSubqueryTest>::new(data_source, get_base_test_params())
+        .use_inputs(Some(Default::default()))
+        .scaffold_output();
+    let compute_query = build_offchain_compute_query(12, &data);
+    let query = build_axiom_v2_query_data(11155111, &data, &compute_query).unwrap();
+    let output = AxiomV2RunOutput {
+        compute_query,
+        data,
+        query: Some(query.clone()),
+        compute_output: Some(serde_json::json!({ "blockNumber": 9730000 })),
+    };
+    // written and read back the way the `run` and `send-query` commands do
+    let output_path =
+        std::env::temp_dir().join(format!("axiom-output-{}.json", std::process::id()));
+    serde_json::to_writer_pretty(fs::File::create(&output_path).unwrap(), &output).unwrap();
+    let deserialized: AxiomV2RunOutput =
+        serde_json::from_reader(fs::File::open(&output_path).unwrap()).unwrap();
+    fs::remove_file(output_path).unwrap();
+    assert_eq!(
+        serde_json::to_value(&deserialized).unwrap(),
+        serde_json::to_value(&output).unwrap()
+    );
+    assert_eq!(deserialized.query.unwrap().query_hash, query.query_hash);
+    assert_eq!(deserialized.compute_output, output.compute_output);
+    for (subquery, entry) in deserialized.data.data_query().iter().zip(entries) {
+        assert_eq!(subquery.subquery(), &entry.subquery);
+        // the subquery data alone is decoded by its fields
+        let raw = serde_json::to_value(&subquery.subquery_data).unwrap();
+        let raw: RawSubquery = serde_json::from_value(raw).unwrap();
+        assert_eq!(raw.0, entry.subquery);
+    }
+}
@@ -0,0 +1,312 @@
+use std::sync::{Arc, Mutex};
+
+use axiom_codec::HiLo;
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{circuit::BaseCircuitParams, RangeChip},
+        AssignedValue,
+    },
+    halo2curves::{bn256::Fr, ff::PrimeField},
+    rlc::circuit::builder::RlcCircuitBuilder,
+};
+use ethers::types::{Address, BigEndianHash, Bytes, H256, U256};
+
+use super::utils::EmptyCircuitInput;
+use crate::{
+    input::{
+        flatten::{BytesInput, FixLenVec, InputFlatten, SignedInt, VarLenVec, VarLenVecInput},
+        raw_input::{RawInput, VarLenBytes, VarLenString},
+    },
+    run::inner::mock,
+    scaffold::{AxiomCircuit, AxiomCircuitScaffold},
+    subquery::{caller::SubqueryCaller, source::InMemoryDataSource},
+    types::AxiomCircuitParams,
+};
+
+macro_rules! input_test_struct {
+    ($struct_name:ident, |$ctx:ident, $range:ident| $body:block) => {
+        #[derive(Debug, Clone, Default)]
+        struct $struct_name;
+        impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for $struct_name {
+            type InputValue = EmptyCircuitInput<Fr>;
+            type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+
+            fn virtual_assign_phase0(
+                builder: &mut RlcCircuitBuilder<Fr>,
+                range: &RangeChip<Fr>,
+                _subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+                _callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+                _inputs: Self::InputWitness,
+            ) {
+                let $ctx = builder.base.main(0);
+                let $range = range;
+                $body
+            }
+        }
+    };
+}
+
+fn get_input_test_params() -> AxiomCircuitParams {
+    AxiomCircuitParams::Base(BaseCircuitParams {
+        k: 12,
+        num_advice_per_phase: vec![4],
+        num_lookup_advice_per_phase: vec![1],
+        num_fixed: 1,
+        num_instance_columns: 1,
+        lookup_bits: Some(11),
+    })
+}
+
+#[test]
+pub fn test_signed_raw_input() {
+    let negative: SignedInt<Fr> = RawInput::<Fr>::convert(&-2i64);
+    assert_eq!(negative.value, Fr::from(u64::MAX - 1));
+    assert_eq!(negative.sign, Fr::one());
+    let positive: SignedInt<Fr> = RawInput::<Fr>::convert(&5i8);
+    assert_eq!(positive.value, Fr::from(5));
+    assert_eq!(positive.sign, Fr::zero());
+    let min: SignedInt<Fr> = RawInput::<Fr>::convert(&i128::MIN);
+    assert_eq!(min.value, Fr::from_u128(1 << 127));
+    let flattened = InputFlatten::<Fr>::flatten_vec(&negative);
+    assert_eq!(SignedInt::unflatten(flattened).unwrap(), negative);
+}
+
+#[test]
+pub fn test_bool_and_u256_raw_input() {
+    assert_eq!(RawInput::<Fr>::convert(&true), Fr::one());
+    assert_eq!(RawInput::<Fr>::convert(&false), Fr::zero());
+    let value = (U256::one() << 200) + 7;
+    let hilo: HiLo<Fr> = RawInput::<Fr>::convert(&value);
+    assert_eq!(hilo.hi(), Fr::from_u128(1 << 72));
+    assert_eq!(hilo.lo(), Fr::from(7));
+}
+
+#[test]
+pub fn test_bytes_raw_input() {
+    let bytes = VarLenBytes::<4>::new(Bytes::from(vec![1, 2, 3])).unwrap();
+    let input: BytesInput<Fr, 4> = RawInput::<Fr>::convert(&bytes);
+    assert_eq!(
+        input.bytes,
+        [Fr::from(1), Fr::from(2), Fr::from(3), Fr::zero()]
+    );
+    assert_eq!(input.len, Fr::from(3));
+    let flattened = InputFlatten::<Fr>::flatten_vec(&input);
+    assert_eq!(
+        flattened.len(),
+        <BytesInput<Fr, 4> as InputFlatten<Fr>>::NUM_FE
+    );
+    assert_eq!(BytesInput::unflatten(flattened).unwrap(), input);
+
+    let string: VarLenString<4> = serde_json::from_str("\"abcd\"").unwrap();
+    let input: BytesInput<Fr, 4> = RawInput::<Fr>::convert(&string);
+    assert_eq!(input.bytes[0], Fr::from(b'a' as u64));
+    assert_eq!(input.len, Fr::from(4));
+
+    assert!(VarLenBytes::<2>::new(Bytes::from(vec![1, 2, 3])).is_err());
+    assert!(serde_json::from_str::<VarLenBytes<2>>("\"0x010203\"").is_err());
+    assert!(serde_json::from_str::<VarLenString<4>>("\"abcde\"").is_err());
+}
+
+#[test]
+pub fn test_var_len_vec_raw_input() {
+    let vec = VarLenVec::<u64, 4>::new(vec![5, 6]).unwrap();
+    let input: VarLenVecInput<Fr, Fr, 4> = RawInput::<Fr>::convert(&vec);
+    assert_eq!(
+        input.values,
+        [Fr::from(5), Fr::from(6), Fr::zero(), Fr::zero()]
+    );
+    assert_eq!(input.len, Fr::from(2));
+    let flattened = InputFlatten::<Fr>::flatten_vec(&input);
+    assert_eq!(flattened.len(), 5);
+    assert_eq!(VarLenVecInput::unflatten(flattened).unwrap(), input);
+
+    let hashes = VarLenVec::<H256, 2>::new(vec![H256::repeat_byte(1)]).unwrap();
+    let input: VarLenVecInput<HiLo<Fr>, Fr, 2> = RawInput::<Fr>::convert(&hashes);
+    let flattened = InputFlatten::<Fr>::flatten_vec(&input);
+    assert_eq!(flattened.len(), 5);
+    assert_eq!(VarLenVecInput::unflatten(flattened).unwrap(), input);
+
+    assert!(VarLenVec::<u64, 1>::new(vec![1, 2]).is_err());
+    assert!(serde_json::from_str::<VarLenVec<u64, 1>>("[1, 2]").is_err());
+    assert!(FixLenVec::<u64, 2>::try_from(vec![1]).is_err());
+    assert!(serde_json::from_str::<FixLenVec<u64, 2>>("[1]").is_err());
+}
+
+#[derive(Debug, Clone, Default)]
+struct VarLenSumTest;
+
+impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for VarLenSumTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        range: &RangeChip<Fr>,
+        _subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+        callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) {
+        let ctx = builder.base.main(0);
+        // padding that is not zero must not be counted either
+        let values = [3, 4, 100, 100].map(|x| ctx.load_witness(Fr::from(x)));
+        let len = ctx.load_witness(Fr::from(2));
+        let input = VarLenVecInput { values, len };
+        let sum = input.sum(ctx, range);
+        let zero = ctx.load_zero();
+        callback.push(HiLo::from_hi_lo([zero, sum]));
+    }
+}
+
+#[test]
+pub fn test_var_len_vec_sum() {
+    let params = get_input_test_params();
+    mock::<_, VarLenSumTest>(
+        InMemoryDataSource::new(),
+        params.clone(),
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+    let results = AxiomCircuit::<_, _, VarLenSumTest>::new(InMemoryDataSource::new(), params)
+        .use_inputs(Some(Default::default()))
+        .scaffold_output()
+        .compute_results;
+    assert_eq!(results[0].into_uint(), U256::from(7));
+}
+
+fn mock_input_test<S: AxiomCircuitScaffold<InMemoryDataSource, Fr>>() {
+    let params = get_input_test_params();
+    mock::<_, S>(
+        InMemoryDataSource::new(),
+        params,
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+}
+
+input_test_struct!(AddressOverflowTest, |ctx, range| {
+    let addr = ctx.load_witness(Fr::from_u128(1 << 96) * Fr::from_u128(1 << 64));
+    <Address as RawInput<Fr>>::constrain(ctx, range, &addr);
+});
+
+#[test]
+#[should_panic]
+pub fn test_address_input_overflow() {
+    mock_input_test::<AddressOverflowTest>();
+}
+
+input_test_struct!(ValidInputTest, |ctx, range| {
+    let flag = ctx.load_witness(RawInput::<Fr>::convert(&true));
+    <bool as RawInput<Fr>>::constrain(ctx, range, &flag);
+    for int in [-2i8, 0, i8::MIN, i8::MAX] {
+        let int: SignedInt<Fr> = RawInput::<Fr>::convert(&int);
+        let int = SignedInt {
+            value: ctx.load_witness(int.value),
+            sign: ctx.load_witness(int.sign),
+        };
+        <i8 as RawInput<Fr>>::constrain(ctx, range, &int);
+    }
+    let int: SignedInt<Fr> = RawInput::<Fr>::convert(&i128::MIN);
+    let int = SignedInt {
+        value: ctx.load_witness(int.value),
+        sign: ctx.load_witness(int.sign),
+    };
+    <i128 as RawInput<Fr>>::constrain(ctx, range, &int);
+    let uint: HiLo<Fr> = RawInput::<Fr>::convert(&U256::MAX);
+    let uint = HiLo::from_hi_lo(uint.hi_lo().map(|x| ctx.load_witness(x)));
+    <U256 as RawInput<Fr>>::constrain(ctx, range, &uint);
+    let bytes = VarLenBytes::<4>::new(Bytes::from(vec![0xff, 0, 7])).unwrap();
+    let bytes: BytesInput<Fr, 4> = RawInput::<Fr>::convert(&bytes);
+    let bytes = BytesInput {
+        bytes: bytes.bytes.map(|x| ctx.load_witness(x)),
+        len: ctx.load_witness(bytes.len),
+    };
+    <VarLenBytes<4> as RawInput<Fr>>::constrain(ctx, range, &bytes);
+});
+
+#[test]
+pub fn test_valid_input_constrain() {
+    mock_input_test::<ValidInputTest>();
+}
+
+input_test_struct!(BoolOverflowTest, |ctx, range| {
+    let flag = ctx.load_witness(Fr::from(2));
+    <bool as RawInput<Fr>>::constrain(ctx, range, &flag);
+});
+
+#[test]
+#[should_panic]
+pub fn test_bool_input_overflow() {
+    mock_input_test::<BoolOverflowTest>();
+}
+
+input_test_struct!(SignedIntMismatchTest, |ctx, range| {
+    // 5 with the sign of a negative number
+    let int = SignedInt {
+        value: ctx.load_witness(Fr::from(5)),
+        sign: ctx.load_witness(Fr::one()),
+    };
+    <i8 as RawInput<Fr>>::constrain(ctx, range, &int);
+});
+
+#[test]
+#[should_panic]
+pub fn test_signed_int_input_sign_mismatch() {
+    mock_input_test::<SignedIntMismatchTest>();
+}
+
+input_test_struct!(SignedIntOverflowTest, |ctx, range| {
+    let int = SignedInt {
+        value: ctx.load_witness(Fr::from(256 + 128)),
+        sign: ctx.load_witness(Fr::one()),
+    };
+    <i8 as RawInput<Fr>>::constrain(ctx, range, &int);
+});
+
+#[test]
+#[should_panic]
+pub fn test_signed_int_input_overflow() {
+    mock_input_test::<SignedIntOverflowTest>();
+}
+
+input_test_struct!(U256OverflowTest, |ctx, range| {
+    let hi = ctx.load_witness(Fr::from_u128(1 << 64) * Fr::from_u128(1 << 64));
+    let lo = ctx.load_zero();
+    <U256 as RawInput<Fr>>::constrain(ctx, range, &HiLo::from_hi_lo([hi, lo]));
+});
+
+#[test]
+#[should_panic]
+pub fn test_u256_input_overflow() {
+    mock_input_test::<U256OverflowTest>();
+}
+
+input_test_struct!(BytesOverflowTest, |ctx, range| {
+    let bytes = BytesInput {
+        bytes: [256, 0].map(|x| ctx.load_witness(Fr::from(x))),
+        len: ctx.load_witness(Fr::one()),
+    };
+    <VarLenString<2> as RawInput<Fr>>::constrain(ctx, range, &bytes);
+});
+
+#[test]
+#[should_panic]
+pub fn test_bytes_input_overflow() {
+    mock_input_test::<BytesOverflowTest>();
+}
+
+input_test_struct!(BytesPaddingTest, |ctx, range| {
+    // the second byte is past `len` but not zero
+    let bytes = BytesInput {
+        bytes: [1, 2].map(|x| ctx.load_witness(Fr::from(x))),
+        len: ctx.load_witness(Fr::one()),
+    };
+    <VarLenBytes<2> as RawInput<Fr>>::constrain(ctx, range, &bytes);
+});
+
+#[test]
+#[should_panic]
+pub fn test_bytes_input_nonzero_padding() {
+    mock_input_test::<BytesPaddingTest>();
+}
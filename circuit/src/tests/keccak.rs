@@ -21,7 +21,7 @@ use axiom_query::{
     },
     verify_compute::utils::verify_snark,
 };
-use ethers::providers::{Http, JsonRpcClient};
+use ethers::providers::{Http, Provider};
 use test_case::test_case;
 
 use super::{
@@ -36,7 +36,7 @@ use crate::{
         inner::{keygen, prove, run},
     },
     scaffold::{AxiomCircuit, AxiomCircuitScaffold},
-    subquery::caller::SubqueryCaller,
+    subquery::{caller::SubqueryCaller, source::SubqueryDataSource},
     tests::utils::{account_call, header_call, EmptyCircuitInput},
     types::AxiomCircuitParams,
     utils::get_provider,
@@ -47,7 +47,7 @@ macro_rules! keccak_test_struct {
     ($struct_name:ident, $subquery_call:ident) => {
         #[derive(Debug, Clone, Default)]
         struct $struct_name;
-        impl<P: JsonRpcClient> AxiomCircuitScaffold<P, Fr> for $struct_name {
+        impl<P: SubqueryDataSource> AxiomCircuitScaffold<P, Fr> for $struct_name {
             type InputValue = EmptyCircuitInput<Fr>;
             type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
 
@@ -115,12 +115,12 @@ keccak_test_struct!(AllSubqueryTest, all_subqueries_call);
 // #[test_case(MappingTest)]
 // #[test_case(TxTest)]
 #[test_case(AllSubqueryTest)]
-pub fn mock<S: AxiomCircuitScaffold<Http, Fr>>(_circuit: S) {
+pub fn mock<S: AxiomCircuitScaffold<Provider<Http>, Fr>>(_circuit: S) {
     let params = get_keccak_test_params();
     let agg_circuit_params = get_agg_test_params();
     let client = get_provider();
-    let (_, pk, pinning) = keygen::<_, S>(client.clone(), params.clone(), None);
-    let snark = prove::<_, S>(client, pinning, None, pk);
+    let (_, pk, pinning) = keygen::<_, S>(client.clone(), params.clone(), None, Default::default());
+    let snark = prove::<_, S>(client, pinning, None, Default::default(), pk).unwrap();
     agg_circuit_mock(agg_circuit_params, snark);
 }
 
@@ -130,7 +130,7 @@ pub fn mock<S: AxiomCircuitScaffold<Http, Fr>>(_circuit: S) {
 #[test_case(StorageTest)]
 #[test_case(MappingTest)]
 #[test_case(TxTest)]
-pub fn test_single_subquery_instances<S: AxiomCircuitScaffold<Http, Fr>>(_circuit: S) {
+pub fn test_single_subquery_instances<S: AxiomCircuitScaffold<Provider<Http>, Fr>>(_circuit: S) {
     let params = get_keccak_test_params();
     let agg_circuit_params = get_agg_test_params();
     let client = get_provider();
@@ -138,8 +138,8 @@ pub fn test_single_subquery_instances<S: AxiomCircuitScaffold<Http, Fr>>(_circui
     let num_user_output_fe = runner.output_num_instances();
     let subquery_fe = runner.subquery_num_instances();
     let results = runner.scaffold_output();
-    let (_, pk, pinning) = keygen::<_, S>(client.clone(), params.clone(), None);
-    let snark = prove::<_, S>(client, pinning, None, pk);
+    let (_, pk, pinning) = keygen::<_, S>(client.clone(), params.clone(), None, Default::default());
+    let snark = prove::<_, S>(client, pinning, None, Default::default(), pk).unwrap();
     let agg_circuit =
         create_aggregation_circuit(agg_circuit_params, snark.clone(), CircuitBuilderStage::Mock);
     let instances = agg_circuit.instances();
@@ -159,12 +159,14 @@ pub fn test_single_subquery_instances<S: AxiomCircuitScaffold<Http, Fr>>(_circui
 // #[test_case(MappingTest)]
 // #[test_case(TxTest)]
 #[test_case(AllSubqueryTest)]
-pub fn test_compute_query<S: AxiomCircuitScaffold<Http, Fr>>(_circuit: S) {
+pub fn test_compute_query<S: AxiomCircuitScaffold<Provider<Http>, Fr>>(_circuit: S) {
     let params = get_keccak_test_params();
     let agg_circuit_params = get_agg_test_params();
     let client = get_provider();
-    let (_vk, pk, pinning) = keygen::<_, S>(client.clone(), params.clone(), None);
-    let output = run::<_, S>(client, pinning, None, pk);
+    let (_vk, pk, pinning) =
+        keygen::<_, S>(client.clone(), params.clone(), None, Default::default());
+    let instance_layout = pinning.instance_layout;
+    let output = run::<_, S>(client, pinning, None, Default::default(), pk).unwrap();
     let (agg_vk, agg_pk, agg_break_points) =
         agg_circuit_keygen(agg_circuit_params, output.snark.clone());
     let final_output = agg_circuit_run(
@@ -173,6 +175,7 @@ pub fn test_compute_query<S: AxiomCircuitScaffold<Http, Fr>>(_circuit: S) {
         agg_pk,
         agg_break_points,
         output.data,
+        instance_layout,
     );
     let circuit = create_aggregation_circuit(
         agg_circuit_params,
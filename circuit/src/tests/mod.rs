@@ -1,5 +1,16 @@
 pub mod base;
+pub mod data_source;
+pub mod input;
 pub mod keccak;
+pub mod native_fields;
+pub mod output;
+pub mod params;
+pub mod public;
+pub mod query;
 pub mod rlc;
 mod shared_tests;
+pub mod solidity;
+pub mod stats;
 mod utils;
+pub mod verify;
+pub mod without_witnesses;
@@ -0,0 +1,184 @@
+use axiom_codec::types::native::{
+    HeaderSubquery, ReceiptSubquery, SolidityNestedMappingSubquery, TxSubquery,
+};
+use ethers::types::{Address, Block, Transaction, TransactionReceipt, H256, U256};
+
+use crate::subquery::{
+    error::InvalidSubqueryReason, header::get_header_field_value_from_block,
+    mapping::get_solidity_nested_mapping_storage_subquery,
+    receipt::get_receipt_field_value_from_receipt, tx::get_tx_field_value_from_tx, HeaderField,
+    ReceiptField, TxField,
+};
+
+fn header_query(field: HeaderField) -> HeaderSubquery {
+    HeaderSubquery {
+        block_number: 1,
+        field_idx: field as u32,
+    }
+}
+
+#[test]
+pub fn test_post_cancun_header_fields() {
+    assert_eq!(HeaderField::BlobGasUsed as u32, 17);
+    assert_eq!(HeaderField::ExcessBlobGas as u32, 18);
+    assert_eq!(HeaderField::ParentBeaconBlockRoot as u32, 19);
+
+    let parent_beacon_block_root = H256::random();
+    let block = Block::<H256> {
+        base_fee_per_gas: Some(U256::from(7)),
+        withdrawals_root: Some(H256::random()),
+        blob_gas_used: Some(U256::from(131072)),
+        excess_blob_gas: Some(U256::from(0)),
+        parent_beacon_block_root: Some(parent_beacon_block_root),
+        ..Default::default()
+    };
+    let value = |field| get_header_field_value_from_block(block.clone(), &header_query(field));
+    assert_eq!(
+        value(HeaderField::BlobGasUsed).unwrap(),
+        H256::from_low_u64_be(131072)
+    );
+    assert_eq!(value(HeaderField::ExcessBlobGas).unwrap(), H256::zero());
+    assert_eq!(
+        value(HeaderField::ParentBeaconBlockRoot).unwrap(),
+        parent_beacon_block_root
+    );
+}
+
+#[test]
+pub fn test_pre_fork_header_fields() {
+    // a pre-London block has none of the fields added since
+    let block = Block::<H256>::default();
+    for (field, name) in [
+        (HeaderField::BaseFeePerGas, "baseFeePerGas"),
+        (HeaderField::WithdrawalsRoot, "withdrawalsRoot"),
+        (HeaderField::BlobGasUsed, "blobGasUsed"),
+        (HeaderField::ExcessBlobGas, "excessBlobGas"),
+        (HeaderField::ParentBeaconBlockRoot, "parentBeaconBlockRoot"),
+    ] {
+        let err = get_header_field_value_from_block(block.clone(), &header_query(field))
+            .unwrap_err()
+            .downcast::<InvalidSubqueryReason>()
+            .unwrap();
+        assert_eq!(err, InvalidSubqueryReason::FieldNotAvailable(name));
+    }
+}
+
+fn tx_value(tx: &Transaction, field_idx: usize) -> anyhow::Result<H256> {
+    let query = TxSubquery {
+        block_number: 1,
+        tx_idx: 0,
+        field_or_calldata_idx: field_idx as u32,
+    };
+    get_tx_field_value_from_tx(tx.clone(), &query)
+}
+
+fn tx_invalid_reason(tx: &Transaction, field_idx: usize) -> InvalidSubqueryReason {
+    tx_value(tx, field_idx)
+        .unwrap_err()
+        .downcast::<InvalidSubqueryReason>()
+        .unwrap()
+}
+
+#[test]
+pub fn test_legacy_tx_missing_fields() {
+    // a pre-EIP-155 contract creation
+    let tx = Transaction {
+        gas_price: Some(U256::from(10)),
+        ..Default::default()
+    };
+    assert_eq!(tx_value(&tx, TxField::To as usize).unwrap(), H256::zero());
+    assert_eq!(
+        tx_value(&tx, TxField::TxType as usize).unwrap(),
+        H256::zero()
+    );
+    assert_eq!(
+        tx_value(&tx, TxField::GasPrice as usize).unwrap(),
+        H256::from_low_u64_be(10)
+    );
+    for (field, name) in [
+        (TxField::ChainId, "chainId"),
+        (TxField::MaxPriorityFeePerGas, "maxPriorityFeePerGas"),
+        (TxField::MaxFeePerGas, "maxFeePerGas"),
+    ] {
+        assert_eq!(
+            tx_invalid_reason(&tx, field as usize),
+            InvalidSubqueryReason::FieldNotAvailable(name)
+        );
+    }
+}
+
+#[test]
+pub fn test_tx_fields_outside_spec() {
+    let tx = Transaction {
+        transaction_type: Some(3.into()),
+        input: vec![1u8; 64].into(),
+        ..Default::default()
+    };
+    // blob and access-list fields are not tx fields of the AxiomV2 spec
+    for field_idx in [12, 57, 58, 80] {
+        assert_eq!(
+            tx_invalid_reason(&tx, field_idx),
+            InvalidSubqueryReason::InvalidFieldIdx(field_idx as u32)
+        );
+    }
+    assert_eq!(
+        tx_invalid_reason(&tx, 1 << 24),
+        InvalidSubqueryReason::InvalidContractDataIdx
+    );
+}
+
+fn receipt_value(receipt: &TransactionReceipt, field: ReceiptField) -> anyhow::Result<H256> {
+    let query = ReceiptSubquery {
+        block_number: 1,
+        tx_idx: 0,
+        field_or_log_idx: field as u32,
+        topic_or_data_or_address_idx: 0,
+        event_schema: H256::zero(),
+    };
+    get_receipt_field_value_from_receipt(receipt.clone(), &query)
+}
+
+#[test]
+pub fn test_post_byzantium_receipt_fields() {
+    // a post-Byzantium receipt has a status instead of a state root, and pre-Berlin nodes do not
+    // return a tx type
+    let receipt = TransactionReceipt {
+        status: Some(1.into()),
+        block_number: Some(5_000_000.into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        receipt_value(&receipt, ReceiptField::Status).unwrap(),
+        H256::from_low_u64_be(1)
+    );
+    assert_eq!(
+        receipt_value(&receipt, ReceiptField::BlockNumber).unwrap(),
+        H256::from_low_u64_be(5_000_000)
+    );
+    for (field, name) in [
+        (ReceiptField::PostState, "root"),
+        (ReceiptField::TxType, "type"),
+    ] {
+        let err = receipt_value(&receipt, field)
+            .unwrap_err()
+            .downcast::<InvalidSubqueryReason>()
+            .unwrap();
+        assert_eq!(err, InvalidSubqueryReason::FieldNotAvailable(name));
+    }
+}
+
+#[test]
+pub fn test_mapping_depth_past_keys() {
+    let query = SolidityNestedMappingSubquery {
+        block_number: 1,
+        addr: Address::zero(),
+        mapping_slot: U256::from(1),
+        mapping_depth: 2,
+        keys: vec![H256::zero()],
+    };
+    let err = get_solidity_nested_mapping_storage_subquery(&query).unwrap_err();
+    assert_eq!(
+        err.downcast::<InvalidSubqueryReason>().unwrap(),
+        InvalidSubqueryReason::InvalidMappingDepth(2)
+    );
+}
@@ -0,0 +1,140 @@
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use axiom_codec::HiLo;
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{circuit::BaseCircuitParams, RangeChip},
+        AssignedValue,
+    },
+    halo2curves::bn256::Fr,
+    rlc::circuit::builder::RlcCircuitBuilder,
+    utils::encode_addr_to_field,
+};
+use ethers::types::{Address, H256, U256};
+
+use super::utils::EmptyCircuitInput;
+use crate::{
+    output::raw_output::RawOutput,
+    run::inner::mock,
+    scaffold::{AxiomCircuit, AxiomCircuitScaffold},
+    subquery::{caller::SubqueryCaller, source::InMemoryDataSource},
+    types::AxiomCircuitParams,
+};
+
+const ADDR: &str = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+
+#[derive(Debug, Clone, Default)]
+struct RawOutputTest;
+
+impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for RawOutputTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        range: &RangeChip<Fr>,
+        _subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+        callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) {
+        let ctx = builder.base.main(0);
+        let flag = ctx.load_witness(Fr::one());
+        callback.push(<bool as RawOutput<Fr>>::constrain(ctx, range, flag));
+        let number = ctx.load_witness(Fr::from(u64::MAX));
+        callback.push(<u64 as RawOutput<Fr>>::constrain(ctx, range, number));
+        let addr = encode_addr_to_field(&Address::from_str(ADDR).unwrap());
+        let addr = ctx.load_witness(addr);
+        callback.push(<Address as RawOutput<Fr>>::constrain(ctx, range, addr));
+        let value = HiLo::<Fr>::from(H256::repeat_byte(0xff)).assign(ctx);
+        callback.push(<U256 as RawOutput<Fr>>::constrain(ctx, range, value));
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct OverflowOutputTest;
+
+impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for OverflowOutputTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        range: &RangeChip<Fr>,
+        _subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+        callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) {
+        let ctx = builder.base.main(0);
+        let number = ctx.load_witness(Fr::from(256));
+        callback.push(<u8 as RawOutput<Fr>>::constrain(ctx, range, number));
+    }
+}
+
+fn get_base_test_params() -> AxiomCircuitParams {
+    let params = BaseCircuitParams {
+        k: 12,
+        num_advice_per_phase: vec![4],
+        num_lookup_advice_per_phase: vec![1],
+        num_fixed: 1,
+        num_instance_columns: 1,
+        lookup_bits: Some(11),
+    };
+    AxiomCircuitParams::Base(params)
+}
+
+#[test]
+pub fn test_raw_output() {
+    mock::<_, RawOutputTest>(
+        InMemoryDataSource::new(),
+        get_base_test_params(),
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+    let results =
+        AxiomCircuit::<_, _, RawOutputTest>::new(InMemoryDataSource::new(), get_base_test_params())
+            .use_inputs(Some(Default::default()))
+            .scaffold_output()
+            .compute_results;
+    assert!(<bool as RawOutput<Fr>>::decode(results[0]).unwrap());
+    assert_eq!(
+        <u64 as RawOutput<Fr>>::decode(results[1]).unwrap(),
+        u64::MAX
+    );
+    assert_eq!(
+        <Address as RawOutput<Fr>>::decode(results[2]).unwrap(),
+        Address::from_str(ADDR).unwrap()
+    );
+    assert_eq!(
+        <U256 as RawOutput<Fr>>::decode(results[3]).unwrap(),
+        U256::MAX
+    );
+    assert_eq!(
+        <H256 as RawOutput<Fr>>::decode(results[3]).unwrap(),
+        H256::repeat_byte(0xff)
+    );
+}
+
+#[test]
+#[should_panic]
+pub fn test_raw_output_overflow() {
+    mock::<_, OverflowOutputTest>(
+        InMemoryDataSource::new(),
+        get_base_test_params(),
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+}
+
+#[test]
+pub fn test_raw_output_decode_overflow() {
+    let value = H256::from_low_u64_be(256);
+    assert!(<u8 as RawOutput<Fr>>::decode(value).is_err());
+    assert!(<bool as RawOutput<Fr>>::decode(H256::from_low_u64_be(2)).is_err());
+    assert!(<Address as RawOutput<Fr>>::decode(H256::repeat_byte(1)).is_err());
+    assert_eq!(<u16 as RawOutput<Fr>>::decode(value).unwrap(), 256);
+}
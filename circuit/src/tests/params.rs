@@ -0,0 +1,290 @@
+use std::sync::{Arc, Mutex};
+
+use axiom_codec::HiLo;
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{circuit::BaseCircuitParams, GateInstructions, RangeChip, RangeInstructions},
+        AssignedValue,
+    },
+    halo2_proofs::plonk::Circuit,
+    halo2curves::bn256::Fr,
+    rlc::circuit::{builder::RlcCircuitBuilder, RlcCircuitParams},
+    utils::keccak::decorator::RlcKeccakCircuitParams,
+};
+
+use super::utils::EmptyCircuitInput;
+use crate::{
+    params::{ParamsCandidate, ParamsObjective, ParamsSearch},
+    run::inner::mock,
+    scaffold::{AxiomCircuit, AxiomCircuitScaffold},
+    subquery::{caller::SubqueryCaller, source::InMemoryDataSource},
+    types::AxiomCircuitParams,
+};
+
+#[derive(Debug, Clone, Default)]
+struct RangeCheckTest;
+
+impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for RangeCheckTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        range: &RangeChip<Fr>,
+        _subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+        _callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) {
+        let ctx = builder.base.main(0);
+        for i in 0..2000u64 {
+            let value = ctx.load_witness(Fr::from(i << 32));
+            range.range_check(ctx, value, 64);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ByteCheckTest;
+
+impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for ByteCheckTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        range: &RangeChip<Fr>,
+        _subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+        _callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) {
+        let ctx = builder.base.main(0);
+        for i in 0..200u64 {
+            let value = ctx.load_witness(Fr::from(i));
+            range.range_check(ctx, value, 8);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct RlcTest;
+
+impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for RlcTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+    type FirstPhasePayload = Vec<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        _range: &RangeChip<Fr>,
+        _subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+        _callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) -> Self::FirstPhasePayload {
+        let ctx = builder.base.main(0);
+        (1..=8).map(|i| ctx.load_witness(Fr::from(i))).collect()
+    }
+
+    fn virtual_assign_phase1(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        range: &RangeChip<Fr>,
+        payload: Self::FirstPhasePayload,
+    ) {
+        let gate = range.gate();
+        let rlc_chip = builder.rlc_chip(gate);
+        let (ctx, rlc_ctx) = builder.rlc_ctx_pair();
+        gate.add(ctx, payload[0], payload[1]);
+        rlc_chip.compute_rlc_fixed_len(rlc_ctx, payload);
+    }
+}
+
+fn get_default_test_params() -> AxiomCircuitParams {
+    AxiomCircuitParams::Base(BaseCircuitParams {
+        k: 12,
+        num_advice_per_phase: vec![4],
+        num_lookup_advice_per_phase: vec![1],
+        num_fixed: 1,
+        num_instance_columns: 1,
+        lookup_bits: Some(11),
+    })
+}
+
+#[test]
+pub fn test_auto_configure_base() {
+    let mut runner = AxiomCircuit::<_, _, RangeCheckTest>::new(
+        InMemoryDataSource::new(),
+        get_default_test_params(),
+    )
+    .use_inputs(Some(Default::default()));
+    runner.auto_configure(13);
+    let params = runner.params();
+    let AxiomCircuitParams::Base(base_params) = params.clone() else {
+        panic!("Expected base params, got {params:?}");
+    };
+    assert_eq!(base_params.k, 13);
+    assert_eq!(base_params.lookup_bits, Some(12));
+    assert!(base_params.num_advice_per_phase[0] > 0);
+    assert!(base_params.num_lookup_advice_per_phase[0] > 0);
+    mock::<_, RangeCheckTest>(
+        InMemoryDataSource::new(),
+        params,
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+
+    // a smaller degree needs more columns
+    runner.auto_configure(12);
+    let AxiomCircuitParams::Base(smaller_params) = runner.params() else {
+        panic!("Expected base params");
+    };
+    assert!(smaller_params.num_advice_per_phase[0] > base_params.num_advice_per_phase[0]);
+}
+
+#[test]
+pub fn test_auto_configure_lookup_bits() {
+    // the lookup bits are the width of the widest range check
+    let mut runner = AxiomCircuit::<_, _, ByteCheckTest>::new(
+        InMemoryDataSource::new(),
+        get_default_test_params(),
+    )
+    .use_inputs(Some(Default::default()));
+    runner.auto_configure(12);
+    let params = runner.params();
+    let AxiomCircuitParams::Base(base_params) = params.clone() else {
+        panic!("Expected base params, got {params:?}");
+    };
+    assert_eq!(base_params.lookup_bits, Some(8));
+    mock::<_, ByteCheckTest>(
+        InMemoryDataSource::new(),
+        params,
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "leaves no usable rows")]
+pub fn test_auto_configure_without_usable_rows() {
+    let mut runner = AxiomCircuit::<_, _, RangeCheckTest>::new(
+        InMemoryDataSource::new(),
+        get_default_test_params(),
+    )
+    .use_inputs(Some(Default::default()));
+    runner.auto_configure(6);
+}
+
+#[test]
+pub fn test_auto_configure_rlc() {
+    let AxiomCircuitParams::Base(base) = get_default_test_params() else {
+        unreachable!()
+    };
+    let params = AxiomCircuitParams::Rlc(RlcCircuitParams {
+        base,
+        num_rlc_columns: 2,
+    });
+    let mut runner = AxiomCircuit::<_, _, RlcTest>::new(InMemoryDataSource::new(), params)
+        .use_inputs(Some(Default::default()));
+    runner.auto_configure(12);
+    let params = runner.params();
+    let AxiomCircuitParams::Rlc(rlc_params) = params.clone() else {
+        panic!("Expected RLC params, got {params:?}");
+    };
+    assert_eq!(rlc_params.num_rlc_columns, 1);
+    assert_eq!(rlc_params.base.lookup_bits, Some(11));
+    mock::<_, RlcTest>(
+        InMemoryDataSource::new(),
+        params,
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+}
+
+#[test]
+pub fn test_search_params() {
+    let mut runner = AxiomCircuit::<_, _, RangeCheckTest>::new(
+        InMemoryDataSource::new(),
+        get_default_test_params(),
+    )
+    .use_inputs(Some(Default::default()));
+    let params = runner.params();
+    let search = runner.search_params(8..=14, ParamsObjective::MinK);
+    // the search leaves the circuit with the params it started with
+    assert_eq!(
+        serde_json::to_string(&runner.params()).unwrap(),
+        serde_json::to_string(&params).unwrap()
+    );
+    assert_eq!(search.candidates.len(), 7);
+    // the instances alone do not fit in 2^8 rows
+    assert!(!search.candidates[0].is_feasible());
+    assert!(search.candidates[0].params.is_none());
+    let best = search.best().unwrap();
+    assert!(search
+        .candidates
+        .iter()
+        .filter(|candidate| candidate.k < best.k)
+        .all(|candidate| !candidate.is_feasible()));
+    mock::<_, RangeCheckTest>(
+        InMemoryDataSource::new(),
+        best.params.clone().unwrap(),
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+
+    let search = runner.search_params(8..=14, ParamsObjective::MinCost);
+    let best = search.best().unwrap();
+    assert!(search
+        .candidates
+        .iter()
+        .filter(|candidate| candidate.is_feasible())
+        .all(|candidate| candidate.cost() >= best.cost()));
+
+    // degrees with every row reserved are rejected without fitting the circuit
+    let search = runner.search_params(0..=6, ParamsObjective::MinK);
+    assert!(search.best().is_none());
+    assert!(search
+        .candidates
+        .iter()
+        .all(|candidate| candidate.params.is_none()));
+}
+
+fn keccak_candidate(k: usize, keccak_rows_per_round: usize) -> ParamsCandidate {
+    let AxiomCircuitParams::Base(base) = get_default_test_params() else {
+        unreachable!()
+    };
+    ParamsCandidate {
+        k,
+        params: Some(AxiomCircuitParams::Keccak(RlcKeccakCircuitParams {
+            keccak_rows_per_round,
+            rlc: RlcCircuitParams {
+                base,
+                num_rlc_columns: 0,
+            },
+        })),
+        infeasible: None,
+    }
+}
+
+#[test]
+pub fn test_keccak_cost() {
+    let base = ParamsCandidate {
+        k: 12,
+        params: Some(get_default_test_params()),
+        infeasible: None,
+    };
+    // the keccak sub-circuit takes more columns with fewer rows per round
+    let dense = keccak_candidate(12, 10);
+    let sparse = keccak_candidate(13, 20);
+    assert!(dense.num_columns() > base.num_columns());
+    assert!(dense.num_columns() > sparse.num_columns());
+
+    // the cheapest degree is picked with the keccak columns counted
+    let search = ParamsSearch::new(
+        ParamsObjective::MinCost,
+        vec![dense.clone(), sparse.clone()],
+    );
+    let best = search.best().unwrap();
+    assert_eq!(best.cost(), dense.cost().min(sparse.cost()));
+}
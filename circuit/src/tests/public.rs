@@ -0,0 +1,133 @@
+use std::sync::{Arc, Mutex};
+
+use axiom_codec::HiLo;
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{circuit::BaseCircuitParams, RangeChip},
+        AssignedValue,
+    },
+    halo2curves::bn256::Fr,
+    rlc::circuit::builder::RlcCircuitBuilder,
+};
+
+use super::utils::EmptyCircuitInput;
+use crate::{
+    run::inner::mock,
+    scaffold::{AxiomCircuit, AxiomCircuitScaffold, LimitError},
+    subquery::{caller::SubqueryCaller, source::InMemoryDataSource},
+    types::{AxiomCircuitParams, AxiomCircuitPinning},
+};
+
+macro_rules! public_test_struct {
+    ($struct_name:ident, $max_public:expr, $values:expr) => {
+        #[derive(Debug, Clone, Default)]
+        struct $struct_name;
+        impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for $struct_name {
+            type InputValue = EmptyCircuitInput<Fr>;
+            type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+            const MAX_PUBLIC_INSTANCES: usize = $max_public;
+
+            fn virtual_assign_phase0(
+                builder: &mut RlcCircuitBuilder<Fr>,
+                _range: &RangeChip<Fr>,
+                subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+                _callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+                _inputs: Self::InputWitness,
+            ) {
+                let mut caller = subquery_caller.lock().unwrap();
+                caller.enter_region("expose", builder);
+                for value in $values {
+                    let value = builder.base.main(0).load_witness(Fr::from(value));
+                    caller.expose_public(value);
+                }
+                caller.exit_region(builder);
+            }
+        }
+    };
+}
+
+public_test_struct!(PublicTest, 2, [7u64]);
+public_test_struct!(PublicOverflowTest, 1, [7u64, 8]);
+
+fn get_public_test_params() -> AxiomCircuitParams {
+    AxiomCircuitParams::Base(BaseCircuitParams {
+        k: 12,
+        num_advice_per_phase: vec![4],
+        num_lookup_advice_per_phase: vec![1],
+        num_fixed: 1,
+        num_instance_columns: 1,
+        lookup_bits: Some(11),
+    })
+}
+
+#[test]
+pub fn test_public_instances() {
+    mock::<_, PublicTest>(
+        InMemoryDataSource::new(),
+        get_public_test_params(),
+        Some(Default::default()),
+        Default::default(),
+    )
+    .unwrap();
+
+    let runner =
+        AxiomCircuit::<_, _, PublicTest>::new(InMemoryDataSource::new(), get_public_test_params())
+            .use_inputs(Some(Default::default()));
+    let layout = runner.instance_layout();
+    assert_eq!(layout.max_public_instances, 2);
+    assert_eq!(layout.num_instance_columns(), 2);
+    // the first column keeps the layout of a circuit without public values
+    let instances = runner.instances();
+    assert_eq!(
+        instances
+            .iter()
+            .map(|column| column.len())
+            .collect::<Vec<_>>(),
+        layout.num_instances()
+    );
+    assert_eq!(
+        instances[0].len(),
+        runner.output_num_instances() + runner.subquery_num_instances()
+    );
+    assert_eq!(instances[1][layout.public()], [Fr::from(7), Fr::zero()]);
+    let AxiomCircuitParams::Base(params) = runner.pinning().params else {
+        panic!("expected base circuit params");
+    };
+    assert_eq!(params.num_instance_columns, 2);
+
+    // the layout is restored from the pinning, including pinnings written before it was added
+    let pinning = AxiomCircuitPinning {
+        params: get_public_test_params(),
+        break_points: Default::default(),
+        instance_layout: layout,
+    };
+    let mut pinning = serde_json::to_value(pinning).unwrap();
+    assert_eq!(pinning["instance_layout"]["maxPublicInstances"], 2);
+    let restored: AxiomCircuitPinning = serde_json::from_value(pinning.clone()).unwrap();
+    assert_eq!(restored.instance_layout, layout);
+    pinning.as_object_mut().unwrap().remove("instance_layout");
+    let legacy: AxiomCircuitPinning = serde_json::from_value(pinning).unwrap();
+    assert_eq!(legacy.instance_layout.max_public_instances, 0);
+}
+
+#[test]
+pub fn test_public_instances_overflow() {
+    let runner = AxiomCircuit::<_, _, PublicOverflowTest>::new(
+        InMemoryDataSource::new(),
+        get_public_test_params(),
+    )
+    .use_inputs(Some(Default::default()));
+    let error = runner.check_limits().unwrap_err();
+    assert_eq!(
+        error,
+        LimitError::PublicInstances {
+            num: 2,
+            max: 1,
+            region: Some("expose".to_string()),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "public instance #2 in region `expose` exceeds limit of 1 (2 values exposed)"
+    );
+}
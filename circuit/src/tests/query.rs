@@ -0,0 +1,161 @@
+use axiom_codec::types::native::{AnySubquery, AxiomV2ComputeQuery, HeaderSubquery};
+use ethers::{
+    abi::{decode, ParamType, Token},
+    types::{Address, Bytes, H256},
+    utils::{id, keccak256},
+};
+
+use crate::{
+    query::{
+        encode_send_query_calldata, AxiomV2Callback, AxiomV2FeeData, SendQueryArgs,
+        DEFAULT_CALLBACK_GAS_LIMIT, DEFAULT_MAX_FEE_PER_GAS, SEND_QUERY_SIGNATURE,
+    },
+    subquery::{types::Subquery, HeaderField},
+    types::AxiomV2DataAndResults,
+    utils::{
+        build_axiom_v2_query_data, build_offchain_compute_query, encode_data_query,
+        get_data_query_hash,
+    },
+};
+
+#[test]
+pub fn test_query_hashes() {
+    let header = AnySubquery::Header(HeaderSubquery {
+        block_number: 9528813,
+        field_idx: HeaderField::Timestamp as u32,
+    });
+    let results = AxiomV2DataAndResults::new(
+        vec![Subquery::new(header, H256::from_low_u64_be(1583460958))],
+        vec![H256::from_low_u64_be(1)],
+    );
+    let encoded = encode_data_query(11155111, &results);
+    // sourceChainId, number of subqueries, subquery type, block number, field index
+    let expected = [
+        &11155111u64.to_be_bytes()[..],
+        &1u16.to_be_bytes(),
+        &1u16.to_be_bytes(),
+        &9528813u32.to_be_bytes(),
+        &(HeaderField::Timestamp as u32).to_be_bytes(),
+    ]
+    .concat();
+    assert_eq!(encoded.to_vec(), expected);
+
+    let subquery_hash = keccak256(&expected[10..]);
+    let data_query_hash = get_data_query_hash(11155111, &results);
+    assert_eq!(
+        data_query_hash.0,
+        keccak256([&11155111u64.to_be_bytes()[..], &subquery_hash].concat())
+    );
+    assert_ne!(data_query_hash, get_data_query_hash(1, &results));
+
+    // a query without compute proof has no query schema
+    let compute_query = AxiomV2ComputeQuery {
+        k: 0,
+        result_len: 1,
+        vkey: vec![],
+        compute_proof: Default::default(),
+    };
+    let query = build_axiom_v2_query_data(11155111, &results, &compute_query).unwrap();
+    assert_eq!(query.encoded_data_query, encoded);
+    assert_eq!(query.data_query_hash, data_query_hash);
+    assert_eq!(query.query_schema, H256::zero());
+    let mut preimage = vec![2u8];
+    preimage.extend(11155111u64.to_be_bytes());
+    preimage.extend(data_query_hash.0);
+    preimage.extend([0u8, 0, 1]);
+    assert_eq!(query.query_hash.0, keccak256(preimage));
+}
+
+#[test]
+pub fn test_send_query_calldata() {
+    let header = AnySubquery::Header(HeaderSubquery {
+        block_number: 9528813,
+        field_idx: HeaderField::Timestamp as u32,
+    });
+    let results = AxiomV2DataAndResults::new(
+        vec![Subquery::new(header, H256::from_low_u64_be(1583460958))],
+        vec![H256::from_low_u64_be(1)],
+    );
+    let compute_query = AxiomV2ComputeQuery {
+        k: 13,
+        result_len: 1,
+        vkey: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+        compute_proof: Bytes::from(vec![3u8; 100]),
+    };
+    let args = SendQueryArgs {
+        source_chain_id: 11155111,
+        callback: AxiomV2Callback {
+            target: Address::repeat_byte(4),
+            extra_data: Bytes::from(vec![5u8; 3]),
+        },
+        fee_data: AxiomV2FeeData::default(),
+        user_salt: H256::repeat_byte(6),
+        refundee: Address::repeat_byte(7),
+    };
+    let calldata = encode_send_query_calldata(&compute_query, &results, &args).unwrap();
+    assert_eq!(calldata[..4], id(SEND_QUERY_SIGNATURE));
+    let tokens = decode(
+        &[
+            ParamType::Uint(64),
+            ParamType::FixedBytes(32),
+            ParamType::Tuple(vec![
+                ParamType::Uint(8),
+                ParamType::Uint(16),
+                ParamType::Array(Box::new(ParamType::FixedBytes(32))),
+                ParamType::Bytes,
+            ]),
+            ParamType::Tuple(vec![ParamType::Address, ParamType::Bytes]),
+            ParamType::Tuple(vec![
+                ParamType::Uint(64),
+                ParamType::Uint(32),
+                ParamType::Uint(64),
+            ]),
+            ParamType::FixedBytes(32),
+            ParamType::Address,
+            ParamType::Bytes,
+        ],
+        &calldata[4..],
+    )
+    .unwrap();
+    assert_eq!(tokens[0], Token::Uint(11155111.into()));
+    assert_eq!(
+        tokens[1],
+        Token::FixedBytes(get_data_query_hash(11155111, &results).0.to_vec())
+    );
+    assert_eq!(
+        tokens[2],
+        Token::Tuple(vec![
+            Token::Uint(13.into()),
+            Token::Uint(1.into()),
+            Token::Array(vec![
+                Token::FixedBytes(vec![1; 32]),
+                Token::FixedBytes(vec![2; 32])
+            ]),
+            Token::Bytes(vec![3; 100]),
+        ])
+    );
+    assert_eq!(
+        tokens[3],
+        Token::Tuple(vec![
+            Token::Address(Address::repeat_byte(4)),
+            Token::Bytes(vec![5; 3])
+        ])
+    );
+    assert_eq!(
+        tokens[4],
+        Token::Tuple(vec![
+            Token::Uint(DEFAULT_MAX_FEE_PER_GAS.into()),
+            Token::Uint(DEFAULT_CALLBACK_GAS_LIMIT.into()),
+            Token::Uint(0.into()),
+        ])
+    );
+    assert_eq!(tokens[5], Token::FixedBytes(vec![6; 32]));
+    assert_eq!(tokens[6], Token::Address(Address::repeat_byte(7)));
+    assert_eq!(
+        tokens[7],
+        Token::Bytes(encode_data_query(11155111, &results).to_vec())
+    );
+    // a circuit verified off-chain cannot be sent to the AxiomV2Query contract
+    let offchain_query = build_offchain_compute_query(13, &results);
+    assert!(encode_send_query_calldata(&offchain_query, &results, &args).is_err());
+}
@@ -9,7 +9,7 @@ use axiom_query::axiom_eth::{
     halo2curves::bn256::Fr,
     rlc::circuit::{builder::RlcCircuitBuilder, RlcCircuitParams},
 };
-use ethers::providers::{Http, JsonRpcClient};
+use ethers::providers::{Http, Provider};
 use test_case::test_case;
 
 use super::{
@@ -18,7 +18,7 @@ use super::{
 };
 use crate::{
     scaffold::{AxiomCircuit, AxiomCircuitScaffold},
-    subquery::caller::SubqueryCaller,
+    subquery::{caller::SubqueryCaller, source::SubqueryDataSource},
     tests::{
         shared_tests::{mock_test, single_instance_test},
         utils::{account_call, header_call, EmptyCircuitInput},
@@ -31,7 +31,7 @@ macro_rules! rlc_test_struct {
     ($struct_name:ident, $subquery_call:ident) => {
         #[derive(Debug, Clone, Default)]
         struct $struct_name;
-        impl<P: JsonRpcClient> AxiomCircuitScaffold<P, Fr> for $struct_name {
+        impl<P: SubqueryDataSource> AxiomCircuitScaffold<P, Fr> for $struct_name {
             type InputValue = EmptyCircuitInput<Fr>;
             type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
 
@@ -79,7 +79,7 @@ rlc_test_struct!(AllSubqueryTest, all_subqueries_call);
 #[test_case(MappingTest)]
 #[test_case(TxTest)]
 #[test_case(AllSubqueryTest)]
-pub fn mock<S: AxiomCircuitScaffold<Http, Fr>>(_circuit: S) {
+pub fn mock<S: AxiomCircuitScaffold<Provider<Http>, Fr>>(_circuit: S) {
     let params = get_rlc_test_params();
     mock_test::<S>(params);
 }
@@ -90,7 +90,7 @@ pub fn mock<S: AxiomCircuitScaffold<Http, Fr>>(_circuit: S) {
 #[test_case(StorageTest)]
 #[test_case(MappingTest)]
 #[test_case(TxTest)]
-pub fn test_single_subquery_instances<S: AxiomCircuitScaffold<Http, Fr>>(_circuit: S) {
+pub fn test_single_subquery_instances<S: AxiomCircuitScaffold<Provider<Http>, Fr>>(_circuit: S) {
     let params = get_rlc_test_params();
     let client = get_provider();
     let runner = AxiomCircuit::<_, _, S>::new(client, params);
@@ -108,7 +108,7 @@ pub fn test_single_subquery_instances<S: AxiomCircuitScaffold<Http, Fr>>(_circui
 // #[test_case(MappingTest)]
 // #[test_case(TxTest)]
 #[test_case(AllSubqueryTest)]
-pub fn test_compute_query<S: AxiomCircuitScaffold<Http, Fr>>(_circuit: S) {
+pub fn test_compute_query<S: AxiomCircuitScaffold<Provider<Http>, Fr>>(_circuit: S) {
     let params = get_rlc_test_params();
     check_compute_proof_and_query_format::<S>(params, false);
 }
@@ -22,7 +22,7 @@ use axiom_query::{
         get_metadata_from_protocol, get_onchain_vk_from_vk, write_onchain_vkey,
     },
 };
-use ethers::providers::Http;
+use ethers::providers::{Http, Provider};
 use itertools::Itertools;
 
 use crate::{
@@ -34,9 +34,9 @@ use crate::{
 
 const NUM_BYTES_ACCUMULATOR: usize = 64;
 
-pub fn mock_test<S: AxiomCircuitScaffold<Http, Fr>>(params: AxiomCircuitParams) {
+pub fn mock_test<S: AxiomCircuitScaffold<Provider<Http>, Fr>>(params: AxiomCircuitParams) {
     let client = get_provider();
-    mock::<_, S>(client, params, None);
+    mock::<_, S>(client, params, None, Default::default()).unwrap();
 }
 
 pub fn single_instance_test(
@@ -152,13 +152,14 @@ pub fn check_compute_query_format(
     );
 }
 
-pub fn check_compute_proof_and_query_format<S: AxiomCircuitScaffold<Http, Fr>>(
+pub fn check_compute_proof_and_query_format<S: AxiomCircuitScaffold<Provider<Http>, Fr>>(
     params: AxiomCircuitParams,
     is_aggregation: bool,
 ) {
     let client = get_provider();
-    let (vk, pk, pinning) = keygen::<_, S>(client.clone(), params.clone(), None);
-    let output = run::<_, S>(client, pinning, None, pk);
+    let (vk, pk, pinning) =
+        keygen::<_, S>(client.clone(), params.clone(), None, Default::default());
+    let output = run::<_, S>(client, pinning, None, Default::default(), pk).unwrap();
     check_compute_proof_format(output.clone(), is_aggregation);
     check_compute_query_format(output, params, vk);
 }
@@ -0,0 +1,78 @@
+use ethers::types::H256;
+
+use crate::solidity::{SolidityCallbackStub, SolidityResult, SolidityType};
+
+#[test]
+pub fn test_solidity_callback_stub() {
+    let stub = SolidityCallbackStub::new("AccountAge", H256::repeat_byte(0xab)).use_results(vec![
+        SolidityResult::new("addr", SolidityType::Address),
+        SolidityResult::new("blockNumber", SolidityType::Uint(64)),
+        SolidityResult::new("balance", SolidityType::Uint(256)),
+        SolidityResult::new("storageHash", SolidityType::Bytes32),
+        SolidityResult::new("isContract", SolidityType::Bool),
+    ]);
+    let source = stub.generate().unwrap();
+    assert!(source.contains("contract AccountAge is AxiomV2Client {"));
+    assert!(source.contains(&format!(
+        "bytes32 public constant QUERY_SCHEMA = 0x{};",
+        "ab".repeat(32)
+    )));
+    assert!(source.contains("require(axiomResults.length == 5,"));
+    let decoded = [
+        "address addr = address(uint160(uint256(axiomResults[0])));",
+        "uint64 blockNumber = uint64(uint256(axiomResults[1]));",
+        "uint256 balance = uint256(axiomResults[2]);",
+        "bytes32 storageHash = axiomResults[3];",
+        "bool isContract = axiomResults[4] != bytes32(0);",
+    ];
+    let positions = decoded
+        .iter()
+        .map(|line| source.find(line).unwrap())
+        .collect::<Vec<_>>();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+pub fn test_invalid_solidity_callback_stub() {
+    let stub = |name: &str, results: Vec<SolidityResult>| {
+        SolidityCallbackStub::new(name, H256::zero())
+            .use_results(results)
+            .generate()
+    };
+    assert!(stub("1Client", vec![]).is_err());
+    assert!(stub("contract", vec![]).is_err());
+    for name in ["address", "return", "uint64", "bytes32", "fixed128x18"] {
+        assert!(stub(
+            "Client",
+            vec![SolidityResult::new(name, SolidityType::Bool)]
+        )
+        .is_err());
+    }
+    // names that only start like a reserved word are allowed
+    assert!(stub(
+        "Client",
+        vec![
+            SolidityResult::new("addressHash", SolidityType::Bool),
+            SolidityResult::new("uintValue", SolidityType::Bool),
+        ]
+    )
+    .is_ok());
+    assert!(stub(
+        "Client",
+        vec![SolidityResult::new("a-b", SolidityType::Bool)]
+    )
+    .is_err());
+    assert!(stub(
+        "Client",
+        vec![SolidityResult::new("a", SolidityType::Uint(12))]
+    )
+    .is_err());
+    assert!(stub(
+        "Client",
+        vec![
+            SolidityResult::new("a", SolidityType::Bool),
+            SolidityResult::new("a", SolidityType::Bytes32),
+        ]
+    )
+    .is_err());
+}
@@ -0,0 +1,97 @@
+use std::sync::{Arc, Mutex};
+
+use axiom_codec::HiLo;
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{circuit::BaseCircuitParams, GateInstructions, RangeChip, RangeInstructions},
+        AssignedValue,
+    },
+    halo2curves::bn256::Fr,
+    rlc::circuit::builder::RlcCircuitBuilder,
+};
+
+use super::utils::EmptyCircuitInput;
+use crate::{
+    scaffold::{AxiomCircuit, AxiomCircuitScaffold},
+    subquery::{caller::SubqueryCaller, source::InMemoryDataSource},
+    types::AxiomCircuitParams,
+};
+
+#[derive(Debug, Clone, Default)]
+struct StatsTest;
+
+impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for StatsTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        range: &RangeChip<Fr>,
+        subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+        callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) {
+        let mut subquery_caller = subquery_caller.lock().unwrap();
+        for i in 0..2u64 {
+            subquery_caller.enter_region("range", builder);
+            let ctx = builder.base.main(0);
+            let value = ctx.load_witness(Fr::from(i));
+            range.range_check(ctx, value, 64);
+            subquery_caller.enter_region("add", builder);
+            let ctx = builder.base.main(0);
+            let sum = range.gate().add(ctx, value, value);
+            subquery_caller.exit_region(builder);
+            subquery_caller.exit_region(builder);
+            let ctx = builder.base.main(0);
+            callback.push(HiLo::from_hi_lo([ctx.load_zero(), sum]));
+        }
+    }
+}
+
+fn get_stats_test_params() -> AxiomCircuitParams {
+    AxiomCircuitParams::Base(BaseCircuitParams {
+        k: 12,
+        num_advice_per_phase: vec![4],
+        num_lookup_advice_per_phase: vec![1],
+        num_fixed: 1,
+        num_instance_columns: 1,
+        lookup_bits: Some(11),
+    })
+}
+
+#[test]
+pub fn test_stats() {
+    let runner =
+        AxiomCircuit::<_, _, StatsTest>::new(InMemoryDataSource::new(), get_stats_test_params())
+            .use_inputs(Some(Default::default()));
+    let stats = runner.stats();
+    assert_eq!(stats.num_outputs, 2);
+    assert!(stats.subqueries.is_empty());
+    assert_eq!(stats.num_keccak_f, 0);
+    assert_eq!(stats.rlc_cells, 0);
+    assert!(stats.advice_cells_per_phase[0] > 0);
+    assert!(stats.fixed_constants > 0);
+    assert!(stats.copy_constraints > 0);
+
+    let names = stats
+        .regions
+        .iter()
+        .map(|region| region.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["range", "range/add"]);
+    let (range, add) = (&stats.regions[0], &stats.regions[1]);
+    assert_eq!((range.calls, add.calls), (2, 2));
+    // a gate is 4 cells
+    assert_eq!(add.advice_cells, 2 * 4);
+    assert!(range.advice_cells > add.advice_cells);
+    // all range checks are made in the region
+    assert_eq!(
+        range.lookup_cells,
+        stats.lookup_cells_per_phase.iter().sum::<usize>()
+    );
+    assert_eq!(add.lookup_cells, 0);
+
+    // the stats do not depend on an earlier witness generation pass
+    runner.instances();
+    assert_eq!(runner.stats(), stats);
+}
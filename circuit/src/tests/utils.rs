@@ -9,7 +9,7 @@ use axiom_query::axiom_eth::{
     halo2_base::AssignedValue, halo2curves::bn256::Fr, rlc::circuit::builder::RlcCircuitBuilder,
     utils::encode_addr_to_field,
 };
-use ethers::{providers::JsonRpcClient, types::H160};
+use ethers::types::H160;
 
 use crate::{
     constant, ctx,
@@ -19,6 +19,7 @@ use crate::{
         caller::SubqueryCaller,
         header::HeaderField,
         receipt::ReceiptField,
+        source::SubqueryDataSource,
         types::{
             AssignedAccountSubquery, AssignedHeaderSubquery, AssignedReceiptSubquery,
             AssignedSolidityNestedMappingSubquery, AssignedStorageSubquery, AssignedTxSubquery,
@@ -42,7 +43,7 @@ impl<T: Copy> InputFlatten<T> for EmptyCircuitInput<T> {
     }
 }
 
-pub fn account_call<P: JsonRpcClient>(
+pub fn account_call<P: SubqueryDataSource>(
     builder: &mut RlcCircuitBuilder<Fr>,
     subquery_caller: Arc<Mutex<SubqueryCaller<P, Fr>>>,
 ) -> HiLo<AssignedValue<Fr>> {
@@ -58,7 +59,7 @@ pub fn account_call<P: JsonRpcClient>(
     balance
 }
 
-pub fn header_call<P: JsonRpcClient>(
+pub fn header_call<P: SubqueryDataSource>(
     builder: &mut RlcCircuitBuilder<Fr>,
     subquery_caller: Arc<Mutex<SubqueryCaller<P, Fr>>>,
 ) -> HiLo<AssignedValue<Fr>> {
@@ -73,7 +74,7 @@ pub fn header_call<P: JsonRpcClient>(
     timestamp
 }
 
-pub fn mapping_call<P: JsonRpcClient>(
+pub fn mapping_call<P: SubqueryDataSource>(
     builder: &mut RlcCircuitBuilder<Fr>,
     subquery_caller: Arc<Mutex<SubqueryCaller<P, Fr>>>,
 ) -> HiLo<AssignedValue<Fr>> {
@@ -110,7 +111,7 @@ pub fn mapping_call<P: JsonRpcClient>(
     val
 }
 
-pub fn receipt_call<P: JsonRpcClient>(
+pub fn receipt_call<P: SubqueryDataSource>(
     builder: &mut RlcCircuitBuilder<Fr>,
     subquery_caller: Arc<Mutex<SubqueryCaller<P, Fr>>>,
 ) -> HiLo<AssignedValue<Fr>> {
@@ -131,7 +132,7 @@ pub fn receipt_call<P: JsonRpcClient>(
     val
 }
 
-pub fn storage_call<P: JsonRpcClient>(
+pub fn storage_call<P: SubqueryDataSource>(
     builder: &mut RlcCircuitBuilder<Fr>,
     subquery_caller: Arc<Mutex<SubqueryCaller<P, Fr>>>,
 ) -> HiLo<AssignedValue<Fr>> {
@@ -153,7 +154,7 @@ pub fn storage_call<P: JsonRpcClient>(
     val
 }
 
-pub fn tx_call<P: JsonRpcClient>(
+pub fn tx_call<P: SubqueryDataSource>(
     builder: &mut RlcCircuitBuilder<Fr>,
     subquery_caller: Arc<Mutex<SubqueryCaller<P, Fr>>>,
 ) -> HiLo<AssignedValue<Fr>> {
@@ -169,7 +170,7 @@ pub fn tx_call<P: JsonRpcClient>(
     val
 }
 
-pub fn all_subqueries_call<P: JsonRpcClient>(
+pub fn all_subqueries_call<P: SubqueryDataSource>(
     builder: &mut RlcCircuitBuilder<Fr>,
     subquery_caller: Arc<Mutex<SubqueryCaller<P, Fr>>>,
 ) -> Vec<HiLo<AssignedValue<Fr>>> {
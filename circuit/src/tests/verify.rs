@@ -0,0 +1,232 @@
+use std::{str::FromStr, sync::Arc};
+
+use axiom_query::axiom_eth::providers::{
+    account::get_acct_rlp,
+    transaction::{get_transaction_rlp, get_tx_key_from_index, BlockWithTransactions},
+};
+use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+use ethers::{
+    types::{
+        Address, BigEndianHash, Block, Bloom, Bytes, EIP1186ProofResponse, Log, StorageProof,
+        Transaction, H256, H64, U256,
+    },
+    utils::{keccak256, rlp},
+};
+use hasher::HasherKeccak;
+
+use crate::subquery::{
+    error::InvalidSubqueryReason,
+    verify::{
+        verify_account_proof, verify_block_hash, verify_storage_proof, verify_transactions_root,
+        TxReceiptLimits,
+    },
+};
+
+fn new_trie() -> PatriciaTrie<MemoryDB, HasherKeccak> {
+    PatriciaTrie::new(Arc::new(MemoryDB::new(true)), Arc::new(HasherKeccak::new()))
+}
+
+fn to_bytes(proof: Vec<Vec<u8>>) -> Vec<Bytes> {
+    proof.into_iter().map(Bytes::from).collect()
+}
+
+/// Builds a state trie of a few accounts that share one storage trie, and returns its state root
+/// along with the `eth_getProof` response for `addr` and `slot`
+fn get_test_proof(addr: Address, slot: U256) -> (H256, EIP1186ProofResponse) {
+    let mut storage_trie = new_trie();
+    for i in 1..=8u64 {
+        let key = keccak256(H256::from_low_u64_be(i));
+        let value = rlp::encode(&U256::from(i * 1000)).to_vec();
+        storage_trie.insert(key.to_vec(), value).unwrap();
+    }
+    let storage_hash = H256::from_slice(&storage_trie.root().unwrap());
+    let slot_key = keccak256(H256::from_uint(&slot));
+    let storage_proof = StorageProof {
+        key: slot,
+        proof: to_bytes(storage_trie.get_proof(&slot_key).unwrap()),
+        value: storage_trie
+            .get(&slot_key)
+            .unwrap()
+            .map(|value| rlp::decode(&value).unwrap())
+            .unwrap_or_default(),
+    };
+
+    let mut state_trie = new_trie();
+    let mut proof = None;
+    for i in 1..=8u64 {
+        let account = EIP1186ProofResponse {
+            address: Address::from_low_u64_be(i),
+            balance: U256::from(i),
+            code_hash: H256::from(keccak256([i as u8])),
+            nonce: i.into(),
+            storage_hash,
+            ..Default::default()
+        };
+        let key = keccak256(account.address);
+        state_trie
+            .insert(key.to_vec(), get_acct_rlp(&account))
+            .unwrap();
+        if account.address == addr {
+            proof = Some(account);
+        }
+    }
+    let state_root = H256::from_slice(&state_trie.root().unwrap());
+    let mut proof = proof.unwrap_or(EIP1186ProofResponse {
+        address: addr,
+        ..Default::default()
+    });
+    proof.account_proof = to_bytes(state_trie.get_proof(&keccak256(addr)).unwrap());
+    proof.storage_proof = vec![storage_proof];
+    (state_root, proof)
+}
+
+#[test]
+pub fn test_verify_account_proof() {
+    let (state_root, proof) = get_test_proof(Address::from_low_u64_be(3), U256::from(2));
+    assert!(verify_account_proof(state_root, &proof).unwrap());
+
+    let mut tampered = proof.clone();
+    tampered.balance += U256::one();
+    assert!(verify_account_proof(state_root, &tampered).is_err());
+
+    assert!(verify_account_proof(H256::random(), &proof).is_err());
+
+    // an exclusion proof shows that the account does not exist
+    let (state_root, proof) = get_test_proof(Address::from_low_u64_be(100), U256::from(2));
+    assert!(!verify_account_proof(state_root, &proof).unwrap());
+}
+
+#[test]
+pub fn test_verify_storage_proof() {
+    for slot in [2, 100] {
+        let (_, proof) = get_test_proof(Address::from_low_u64_be(3), U256::from(slot));
+        let storage_proof = &proof.storage_proof[0];
+        verify_storage_proof(proof.storage_hash, storage_proof).unwrap();
+
+        let mut tampered = storage_proof.clone();
+        tampered.value += U256::one();
+        assert!(verify_storage_proof(proof.storage_hash, &tampered).is_err());
+    }
+}
+
+#[test]
+pub fn test_verify_transactions_root() {
+    let transactions = (0..20u64)
+        .map(|i| Transaction {
+            nonce: i.into(),
+            value: U256::from(i * 1000),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    let mut trie = new_trie();
+    for (idx, tx) in transactions.iter().enumerate() {
+        let tx_rlp = get_transaction_rlp(tx).unwrap().to_vec();
+        trie.insert(get_tx_key_from_index(idx), tx_rlp).unwrap();
+    }
+    let block = BlockWithTransactions {
+        number: 1.into(),
+        transactions_root: H256::from_slice(&trie.root().unwrap()),
+        transactions,
+    };
+    verify_transactions_root(block.clone()).unwrap();
+
+    let mut tampered = block;
+    tampered.transactions[7].value += U256::one();
+    assert!(verify_transactions_root(tampered).is_err());
+}
+
+#[test]
+pub fn test_verify_block_hash() {
+    // the mainnet genesis block
+    let block = Block::<H256> {
+        uncles_hash: H256::from_str(
+            "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+        )
+        .unwrap(),
+        author: Some(Address::zero()),
+        state_root: H256::from_str(
+            "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+        )
+        .unwrap(),
+        transactions_root: H256(keccak256(rlp::NULL_RLP)),
+        receipts_root: H256(keccak256(rlp::NULL_RLP)),
+        logs_bloom: Some(Bloom::zero()),
+        difficulty: U256::from(0x400000000u64),
+        number: Some(0.into()),
+        gas_limit: U256::from(5000),
+        extra_data: Bytes::from_str(
+            "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+        )
+        .unwrap(),
+        mix_hash: Some(H256::zero()),
+        nonce: Some(H64::from_low_u64_be(0x42)),
+        ..Default::default()
+    };
+    let genesis_hash =
+        H256::from_str("0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3")
+            .unwrap();
+    assert_eq!(
+        verify_block_hash(&block, Some(genesis_hash)).unwrap(),
+        genesis_hash
+    );
+    assert!(verify_block_hash(&block, Some(H256::zero())).is_err());
+
+    // without a trusted hash, the header is checked against its own hash
+    let mut tampered = Block {
+        hash: Some(genesis_hash),
+        ..block
+    };
+    verify_block_hash(&tampered, None).unwrap();
+    tampered.state_root = H256::zero();
+    assert!(verify_block_hash(&tampered, None).is_err());
+}
+
+fn invalid_reason(result: anyhow::Result<()>) -> InvalidSubqueryReason {
+    result
+        .unwrap_err()
+        .downcast::<InvalidSubqueryReason>()
+        .unwrap()
+}
+
+#[test]
+pub fn test_tx_receipt_limits() {
+    let limits = TxReceiptLimits::default();
+    let tx = Transaction::default();
+    limits.check_tx(&tx).unwrap();
+
+    let blob_tx = Transaction {
+        transaction_type: Some(3.into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        invalid_reason(limits.check_tx(&blob_tx)),
+        InvalidSubqueryReason::UnsupportedTxType(3)
+    );
+
+    let large_tx = Transaction {
+        input: vec![1; 8193].into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        invalid_reason(limits.check_tx(&large_tx)),
+        InvalidSubqueryReason::TxDataTooLong {
+            len: 8193,
+            max: 8192
+        }
+    );
+
+    let logs = vec![Log::default(); 20];
+    limits.check_receipt_logs(&logs).unwrap();
+    assert_eq!(
+        invalid_reason(limits.check_receipt_logs(&vec![Log::default(); 21])),
+        InvalidSubqueryReason::TooManyLogs { num: 21, max: 20 }
+    );
+    let large_log = Log {
+        data: vec![1; 801].into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        invalid_reason(limits.check_receipt_logs(&[large_log])),
+        InvalidSubqueryReason::LogDataTooLong { len: 801, max: 800 }
+    );
+}
@@ -0,0 +1,96 @@
+use std::sync::{Arc, Mutex};
+
+use axiom_codec::HiLo;
+use axiom_query::axiom_eth::{
+    halo2_base::{
+        gates::{circuit::BaseCircuitParams, GateInstructions, RangeChip, RangeInstructions},
+        AssignedValue,
+    },
+    halo2_proofs::{
+        plonk::{keygen_vk, Circuit},
+        poly::kzg::commitment::ParamsKZG,
+    },
+    halo2curves::bn256::{Bn256, Fr},
+    rlc::circuit::builder::RlcCircuitBuilder,
+};
+use ethers::core::rand::{rngs::StdRng, SeedableRng};
+
+use super::utils::EmptyCircuitInput;
+use crate::{
+    scaffold::{AxiomCircuit, AxiomCircuitScaffold},
+    subquery::{caller::SubqueryCaller, source::InMemoryDataSource},
+    types::AxiomCircuitParams,
+};
+
+#[derive(Debug, Clone, Default)]
+struct WithoutWitnessesTest;
+
+impl AxiomCircuitScaffold<InMemoryDataSource, Fr> for WithoutWitnessesTest {
+    type InputValue = EmptyCircuitInput<Fr>;
+    type InputWitness = EmptyCircuitInput<AssignedValue<Fr>>;
+    type FirstPhasePayload = Vec<AssignedValue<Fr>>;
+
+    fn virtual_assign_phase0(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        range: &RangeChip<Fr>,
+        _subquery_caller: Arc<Mutex<SubqueryCaller<InMemoryDataSource, Fr>>>,
+        callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
+        _inputs: Self::InputWitness,
+    ) -> Self::FirstPhasePayload {
+        let ctx = builder.base.main(0);
+        let values = (1..=100u64)
+            .map(|i| ctx.load_witness(Fr::from(i << 20)))
+            .collect::<Vec<_>>();
+        for value in values.iter() {
+            range.range_check(ctx, *value, 64);
+        }
+        callback.push(HiLo::from_hi_lo([ctx.load_zero(), values[0]]));
+        values
+    }
+
+    fn virtual_assign_phase1(
+        builder: &mut RlcCircuitBuilder<Fr>,
+        range: &RangeChip<Fr>,
+        payload: Self::FirstPhasePayload,
+    ) {
+        let gate = range.gate();
+        let rlc_chip = builder.rlc_chip(gate);
+        let (ctx, rlc_ctx) = builder.rlc_ctx_pair();
+        gate.add(ctx, payload[0], payload[1]);
+        rlc_chip.compute_rlc_fixed_len(rlc_ctx, payload);
+    }
+}
+
+#[test]
+pub fn test_without_witnesses() {
+    let params = AxiomCircuitParams::Base(BaseCircuitParams {
+        k: 10,
+        num_advice_per_phase: vec![4],
+        num_lookup_advice_per_phase: vec![1],
+        num_fixed: 1,
+        num_instance_columns: 1,
+        lookup_bits: Some(9),
+    });
+    let mut circuit =
+        AxiomCircuit::<_, _, WithoutWitnessesTest>::new(InMemoryDataSource::new(), params)
+            .use_inputs(Some(Default::default()));
+    circuit.auto_configure(12);
+    let kzg_params = ParamsKZG::<Bn256>::setup(12, StdRng::seed_from_u64(0));
+    let vk = keygen_vk(&kzg_params, &circuit).unwrap();
+    let pinning = circuit.pinning();
+
+    // a prover only assigns witnesses, so keys are generated from its witness-free variant
+    let prover = AxiomCircuit::<_, _, WithoutWitnessesTest>::prover(
+        InMemoryDataSource::new(),
+        pinning.clone(),
+    )
+    .use_inputs(Some(Default::default()));
+    let without_witnesses = prover.without_witnesses();
+    assert!(without_witnesses.inputs.is_none());
+    assert_eq!(
+        serde_json::to_value(without_witnesses.pinning()).unwrap(),
+        serde_json::to_value(&pinning).unwrap()
+    );
+    let vk_without_witnesses = keygen_vk(&kzg_params, &without_witnesses).unwrap();
+    assert_eq!(vk.transcript_repr(), vk_without_witnesses.transcript_repr());
+}
@@ -1,4 +1,9 @@
-use axiom_codec::types::native::AxiomV2ComputeQuery;
+use std::ops::Range;
+
+use axiom_codec::{
+    constants::{USER_MAX_OUTPUTS, USER_MAX_SUBQUERIES, USER_RESULT_FIELD_ELEMENTS},
+    types::{field_elements::SUBQUERY_RESULT_LEN, native::AxiomV2ComputeQuery},
+};
 use axiom_query::axiom_eth::{
     halo2_base::gates::circuit::{BaseCircuitParams, BaseConfig},
     rlc::{
@@ -9,7 +14,7 @@ use axiom_query::axiom_eth::{
     utils::keccak::decorator::{RlcKeccakCircuitParams, RlcKeccakConfig},
     Field,
 };
-use ethers::types::H256;
+use ethers::types::{Bytes, H256};
 use serde::{Deserialize, Serialize};
 
 use crate::subquery::types::Subquery;
@@ -38,15 +43,99 @@ impl Default for AxiomCircuitParams {
 pub struct AxiomCircuitPinning {
     pub params: AxiomCircuitParams,
     pub break_points: RlcThreadBreakPoints,
+    #[serde(default)]
+    pub instance_layout: AxiomInstanceLayout,
+}
+
+/// The layout of the instance columns: the compute results, then the subquery results, in the
+/// first column, and the values exposed publicly in a second column, each padded with zeros to its
+/// maximum. The second column is only added if `max_public_instances > 0`, so the first column
+/// always has the layout that the AxiomV2Query contract expects.
+///
+/// The AxiomV2Query contract verifies circuits with a single instance column, so a circuit with
+/// `max_public_instances > 0` can only be verified off-chain: its compute query has no vkey or
+/// proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AxiomInstanceLayout {
+    pub max_user_outputs: usize,
+    pub max_user_subqueries: usize,
+    #[serde(default)]
+    pub max_public_instances: usize,
+}
+
+impl Default for AxiomInstanceLayout {
+    fn default() -> Self {
+        Self {
+            max_user_outputs: USER_MAX_OUTPUTS,
+            max_user_subqueries: USER_MAX_SUBQUERIES,
+            max_public_instances: 0,
+        }
+    }
+}
+
+impl AxiomInstanceLayout {
+    /// The instances holding the hi-lo pairs of the compute results
+    pub fn outputs(&self) -> Range<usize> {
+        0..self.max_user_outputs * USER_RESULT_FIELD_ELEMENTS
+    }
+
+    /// The instances holding the subqueries and their results
+    pub fn subqueries(&self) -> Range<usize> {
+        let start = self.outputs().end;
+        start..start + self.max_user_subqueries * SUBQUERY_RESULT_LEN
+    }
+
+    /// The instances of the second column holding the values exposed publicly, which are only
+    /// checked by verifiers outside of Axiom
+    pub fn public(&self) -> Range<usize> {
+        0..self.max_public_instances
+    }
+
+    pub fn num_instance_columns(&self) -> usize {
+        if self.max_public_instances > 0 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// The number of instances in each instance column
+    pub fn num_instances(&self) -> Vec<usize> {
+        let mut num_instances = vec![self.subqueries().end];
+        if self.max_public_instances > 0 {
+            num_instances.push(self.max_public_instances);
+        }
+        num_instances
+    }
 }
 
-#[derive(Debug, Serialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AxiomV2DataAndResults {
     pub(crate) data_query: Vec<Subquery>,
     pub(crate) compute_results: Vec<H256>,
 }
 
+impl AxiomV2DataAndResults {
+    pub fn new(data_query: Vec<Subquery>, compute_results: Vec<H256>) -> Self {
+        Self {
+            data_query,
+            compute_results,
+        }
+    }
+
+    pub fn data_query(&self) -> &[Subquery] {
+        &self.data_query
+    }
+
+    pub fn compute_results(&self) -> &[H256] {
+        &self.compute_results
+    }
+}
+
+/// The output of a run, serialized without the snark. The snark is not part of `output.json`, so
+/// [AxiomV2RunOutput] is used to read it back.
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AxiomV2CircuitOutput {
@@ -57,6 +146,48 @@ pub struct AxiomV2CircuitOutput {
     pub snark: Snark,
 }
 
+/// The contents of the `output.json` written by the `run` command
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AxiomV2RunOutput {
+    pub compute_query: AxiomV2ComputeQuery,
+    #[serde(flatten)]
+    pub data: AxiomV2DataAndResults,
+    /// The data query and hashes of the query, if the source chain id is known
+    #[serde(flatten)]
+    pub query: Option<AxiomV2QueryData>,
+    /// The compute results decoded to the `Output` type of the compute function, if it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compute_output: Option<serde_json::Value>,
+}
+
+impl AxiomV2RunOutput {
+    pub fn new(
+        output: AxiomV2CircuitOutput,
+        query: Option<AxiomV2QueryData>,
+        compute_output: Option<serde_json::Value>,
+    ) -> Self {
+        Self {
+            compute_query: output.compute_query,
+            data: output.data,
+            query,
+            compute_output,
+        }
+    }
+}
+
+/// The data query and hashes of a query as the AxiomV2Query contract expects them
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AxiomV2QueryData {
+    pub source_chain_id: u64,
+    /// The `dataQuery` bytes passed to `sendQuery`
+    pub encoded_data_query: Bytes,
+    pub data_query_hash: H256,
+    pub query_schema: H256,
+    pub query_hash: H256,
+}
+
 impl From<AxiomCircuitParams> for RlcKeccakCircuitParams {
     fn from(value: AxiomCircuitParams) -> Self {
         match value {
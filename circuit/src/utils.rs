@@ -1,39 +1,51 @@
 use std::env;
 
+use anyhow::{bail, Result};
 use axiom_codec::{
     constants::USER_MAX_OUTPUTS,
-    types::native::{AxiomV2ComputeQuery, AxiomV2ComputeSnark},
+    encoder::native::{get_query_hash_v2, get_query_schema_hash},
+    types::native::{AxiomV2ComputeQuery, AxiomV2ComputeSnark, AxiomV2DataQuery, Subquery},
     HiLo,
 };
 use axiom_query::{
     axiom_eth::{
         halo2_base::{
             gates::{GateInstructions, RangeChip, RangeInstructions},
-            utils::{biguint_to_fe, modulus},
+            utils::{biguint_to_fe, fs::gen_srs, modulus},
             AssignedValue, Context,
             QuantumCell::Constant,
         },
+        halo2_proofs::plonk::VerifyingKey,
         halo2curves::bn256::G1Affine,
-        snark_verifier::pcs::{
-            kzg::{KzgAccumulator, LimbsEncoding},
-            AccumulatorEncoding,
+        snark_verifier::{
+            pcs::{
+                kzg::{KzgAccumulator, LimbsEncoding},
+                AccumulatorEncoding,
+            },
+            system::halo2::{compile, Config},
         },
         snark_verifier_sdk::{NativeLoader, Snark, BITS, LIMBS},
         utils::{keccak::decorator::RlcKeccakCircuitParams, snark_verifier::NUM_FE_ACCUMULATOR},
         Field,
     },
     verify_compute::utils::{
-        get_metadata_from_protocol, get_onchain_vk_from_protocol, write_onchain_vkey,
+        get_metadata_from_protocol, get_onchain_vk_from_protocol, get_onchain_vk_from_vk,
+        write_onchain_vkey,
     },
 };
 use dotenv::dotenv;
-use ethers::providers::{Http, Provider};
+use ethers::{
+    providers::{Http, Provider},
+    types::{Bytes, H256},
+};
 use itertools::Itertools;
 use num_bigint::BigUint;
 use num_integer::Integer;
 use num_traits::One;
 
-use crate::types::{AxiomCircuitParams, AxiomV2DataAndResults};
+use crate::types::{
+    AxiomCircuitParams, AxiomCircuitPinning, AxiomV2DataAndResults, AxiomV2QueryData,
+};
 
 pub fn build_axiom_v2_compute_query(
     snark: Snark,
@@ -72,6 +84,126 @@ pub fn build_axiom_v2_compute_query(
     }
 }
 
+/// Returns a compute query of degree `k` for `results` without a vkey or proof, for circuits that
+/// cannot be verified on chain as they are
+pub fn build_offchain_compute_query(
+    k: usize,
+    results: &AxiomV2DataAndResults,
+) -> AxiomV2ComputeQuery {
+    AxiomV2ComputeQuery {
+        k: k as u8,
+        result_len: results.compute_results.len() as u16,
+        vkey: vec![],
+        compute_proof: Bytes::default(),
+    }
+}
+
+/// Returns the data query of `results` for the chain with id `source_chain_id`
+pub fn build_axiom_v2_data_query(
+    source_chain_id: u64,
+    results: &AxiomV2DataAndResults,
+) -> AxiomV2DataQuery {
+    let subqueries = results
+        .data_query
+        .iter()
+        .map(|subquery| Subquery::from(subquery.subquery().clone()))
+        .collect();
+    AxiomV2DataQuery {
+        source_chain_id,
+        subqueries,
+    }
+}
+
+/// Returns the `dataQuery` bytes passed to `sendQuery` on the AxiomV2Query contract:
+/// `abi.encodePacked(sourceChainId, subqueries.length, subqueries)`, where each subquery is
+/// `abi.encodePacked(subqueryType, subqueryData)`
+pub fn encode_data_query(source_chain_id: u64, results: &AxiomV2DataAndResults) -> Bytes {
+    let data_query = build_axiom_v2_data_query(source_chain_id, results);
+    let mut encoded = source_chain_id.to_be_bytes().to_vec();
+    encoded.extend((data_query.subqueries.len() as u16).to_be_bytes());
+    for subquery in &data_query.subqueries {
+        encoded.extend(subquery.encode());
+    }
+    encoded.into()
+}
+
+/// Returns the `dataQueryHash` of `results` for the chain with id `source_chain_id`
+pub fn get_data_query_hash(source_chain_id: u64, results: &AxiomV2DataAndResults) -> H256 {
+    build_axiom_v2_data_query(source_chain_id, results).keccak()
+}
+
+/// Returns the `querySchema` of `compute_query`, which is zero if there is no compute proof
+pub fn get_query_schema(compute_query: &AxiomV2ComputeQuery) -> Result<H256> {
+    Ok(get_query_schema_hash(
+        compute_query.k,
+        compute_query.result_len,
+        &compute_query.vkey,
+    )?)
+}
+
+/// Returns the `querySchema` of the circuit with verifying key `vk` and `pinning`, for compute
+/// queries with `result_len` results, as `run` would compute it from a proof
+///
+/// Fails for circuits whose compute query has no vkey, see [build_offchain_compute_query]
+pub fn get_query_schema_from_vk(
+    vk: &VerifyingKey<G1Affine>,
+    pinning: &AxiomCircuitPinning,
+    result_len: usize,
+) -> Result<H256> {
+    if pinning.instance_layout.max_public_instances > 0 {
+        bail!("A circuit with public instances can only be verified off-chain");
+    }
+    if let AxiomCircuitParams::Keccak(_) = pinning.params {
+        bail!("A circuit with keccak must be aggregated before it is verified on chain");
+    }
+    let rlc_keccak_params = RlcKeccakCircuitParams::from(pinning.params.clone());
+    let k = rlc_keccak_params.k();
+    let params = gen_srs(k as u32);
+    let protocol = compile(
+        &params,
+        vk,
+        Config::kzg().with_num_instance(pinning.instance_layout.num_instances()),
+    );
+    let metadata =
+        get_metadata_from_protocol(&protocol, rlc_keccak_params.rlc, USER_MAX_OUTPUTS).unwrap();
+    let onchain_vk = get_onchain_vk_from_vk(vk, metadata);
+    get_query_schema(&AxiomV2ComputeQuery {
+        k: k as u8,
+        result_len: result_len as u16,
+        vkey: write_onchain_vkey(&onchain_vk).unwrap(),
+        compute_proof: Bytes::default(),
+    })
+}
+
+/// Returns the `queryHash` of the data query of `results` together with `compute_query`
+pub fn get_query_hash(
+    source_chain_id: u64,
+    results: &AxiomV2DataAndResults,
+    compute_query: &AxiomV2ComputeQuery,
+) -> Result<H256> {
+    let data_query = build_axiom_v2_data_query(source_chain_id, results);
+    Ok(get_query_hash_v2(
+        source_chain_id,
+        &data_query,
+        compute_query,
+    )?)
+}
+
+/// Returns the encoded data query and all query hashes of a circuit output
+pub fn build_axiom_v2_query_data(
+    source_chain_id: u64,
+    results: &AxiomV2DataAndResults,
+    compute_query: &AxiomV2ComputeQuery,
+) -> Result<AxiomV2QueryData> {
+    Ok(AxiomV2QueryData {
+        source_chain_id,
+        encoded_data_query: encode_data_query(source_chain_id, results),
+        data_query_hash: get_data_query_hash(source_chain_id, results),
+        query_schema: get_query_schema(compute_query)?,
+        query_hash: get_query_hash(source_chain_id, results, compute_query)?,
+    })
+}
+
 pub fn get_provider() -> Provider<Http> {
     dotenv().ok();
     Provider::<Http>::try_from(env::var("PROVIDER_URI").expect("PROVIDER_URI not set")).unwrap()
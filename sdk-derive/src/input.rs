@@ -1,21 +1,150 @@
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::ToTokens;
-use syn::{parse_quote, Data, DeriveInput, GenericParam, Generics, ItemStruct};
+use syn::{
+    meta::ParseNestedMeta, parse_quote, Attribute, Data, DeriveInput, Expr, Fields, GenericParam,
+    Generics, ItemStruct, LitInt,
+};
+
+/// The arguments of `#[AxiomComputeInput(..)]` and `#[axiom_input(..)]`
+#[derive(Default)]
+pub struct InputArgs {
+    /// The name of the generated circuit struct
+    pub circuit: Option<Ident>,
+}
 
-pub fn impl_new_struct(ast: &ItemStruct) -> Result<TokenStream, TokenStream> {
-    let name = &ast.ident;
+impl InputArgs {
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("circuit") {
+            self.circuit = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported argument, expected `circuit = Name`"))
+        }
+    }
+
+    /// Parses the `#[axiom_input(..)]` helper attributes of a derive input
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut args = Self::default();
+        for attr in attrs {
+            if attr.path().is_ident("axiom_input") {
+                attr.parse_nested_meta(|meta| args.parse(meta))?;
+            }
+        }
+        Ok(args)
+    }
 
-    let ends_with_input = name.to_string().ends_with("Input");
-    let name_prefix = name.to_string().trim_end_matches("Input").to_string();
-    let circuit_input_name = format!("{}CircuitInput", name_prefix);
-    let circuit_input_name_ident = Ident::new(&circuit_input_name, Span::call_site());
+    /// Returns the name of the circuit struct of `name`: the `circuit` argument if given,
+    /// otherwise `name` with the `Input` suffix replaced with `CircuitInput`
+    pub fn circuit_name(&self, name: &Ident, require_suffix: bool) -> Result<Ident, TokenStream> {
+        if let Some(circuit) = &self.circuit {
+            return Ok(circuit.clone());
+        }
+        let name = name.to_string();
+        if require_suffix && !name.ends_with("Input") {
+            return Err(quote! {
+                compile_error!("Struct must be named `_Input` (ex: `ExampleInput`), or the circuit struct must be named with `circuit = Name`");
+            });
+        }
+        let name_prefix = name.trim_end_matches("Input");
+        Ok(Ident::new(
+            &format!("{}CircuitInput", name_prefix),
+            Span::call_site(),
+        ))
+    }
+}
 
-    if !ends_with_input {
-        return Err(quote! {
-            compile_error!("Struct must be named `_Input`. Ex: `ExampleInput`");
+/// The field attributes of `#[AxiomComputeInput]`
+const FIELD_ATTRIBUTES: [&str; 3] = ["range", "max", "public"];
+
+/// Removes the field attributes of `#[AxiomComputeInput]` from `ast`, which are not attributes
+/// of the emitted struct
+pub fn strip_field_attrs(ast: &mut ItemStruct) {
+    for field in ast.fields.iter_mut() {
+        field.attrs.retain(|attr| {
+            !FIELD_ATTRIBUTES
+                .iter()
+                .any(|name| attr.path().is_ident(name))
         });
     }
+}
 
+/// Returns the `AxiomComputeInput` items exposing the fields of `ast` marked `#[public]`
+pub fn impl_public_inputs(ast: &ItemStruct) -> TokenStream {
+    let public_fields: Vec<_> = ast
+        .fields
+        .iter()
+        .filter(|field| {
+            field
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("public"))
+        })
+        .collect();
+    let field_names = public_fields.iter().map(|field| &field.ident);
+    let field_types = public_fields.iter().map(|field| &field.ty);
+    quote! {
+        const NUM_PUBLIC_INPUTS: usize = 0 #(+ <<#field_types as axiom_circuit::input::raw_input::RawInput<axiom_sdk::Fr>>::FEType<axiom_sdk::Fr> as axiom_circuit::input::flatten::InputFlatten<axiom_sdk::Fr>>::NUM_FE)*;
+        fn public_inputs(
+            assigned_inputs: &Self::Input<axiom_circuit::axiom_eth::halo2_base::AssignedValue<axiom_sdk::Fr>>,
+        ) -> Vec<axiom_circuit::axiom_eth::halo2_base::AssignedValue<axiom_sdk::Fr>> {
+            let public_inputs: Vec<Vec<axiom_circuit::axiom_eth::halo2_base::AssignedValue<axiom_sdk::Fr>>> = vec![
+                #(axiom_circuit::input::flatten::InputFlatten::<axiom_circuit::axiom_eth::halo2_base::AssignedValue<axiom_sdk::Fr>>::flatten_vec(&assigned_inputs.#field_names)),*
+            ];
+            public_inputs.concat()
+        }
+    }
+}
+
+/// Returns the statements constraining `witness`, the assigned struct of `ast`: the constraints
+/// of each field type, then those of the `#[range(bits = N)]` and `#[max(N)]` field attributes
+pub fn impl_constrain(ast: &ItemStruct) -> Result<TokenStream, TokenStream> {
+    let mut constraints = vec![];
+    for field in ast.fields.iter() {
+        let Some(name) = &field.ident else {
+            return Err(quote! {
+                compile_error!("AxiomComputeInput macro only supports named fields");
+            });
+        };
+        let field_type = &field.ty;
+        constraints.push(quote! {
+            <#field_type as axiom_circuit::input::raw_input::RawInput<F>>::constrain(ctx, range, &witness.#name);
+        });
+        for attr in field.attrs.iter() {
+            if attr.path().is_ident("range") {
+                let mut bits = None;
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("bits") {
+                        bits = Some(meta.value()?.parse::<LitInt>()?);
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported argument, expected `bits = N`"))
+                    }
+                })
+                .map_err(|err| err.to_compile_error())?;
+                let Some(bits) = bits else {
+                    return Err(
+                        syn::Error::new_spanned(attr, "expected `#[range(bits = N)]`")
+                            .to_compile_error(),
+                    );
+                };
+                constraints.push(quote! {
+                    range.range_check(ctx, witness.#name, #bits);
+                });
+            } else if attr.path().is_ident("max") {
+                let max: Expr = attr.parse_args().map_err(|err| err.to_compile_error())?;
+                constraints.push(quote! {
+                    range.check_less_than_safe(ctx, witness.#name, (#max) as u64 + 1);
+                });
+            }
+        }
+    }
+    Ok(quote! { #(#constraints)* })
+}
+
+pub fn impl_new_struct(
+    ast: &ItemStruct,
+    circuit_input_name_ident: &Ident,
+) -> Result<TokenStream, TokenStream> {
     let fields = ast.fields.clone();
 
     let field_types: Vec<_> = fields
@@ -48,9 +177,11 @@ pub fn impl_new_struct(ast: &ItemStruct) -> Result<TokenStream, TokenStream> {
     let input_struct_tokens: Vec<_> = field_names
         .iter()
         .zip(field_types.iter())
-        .map(|(name, field_type)| {
+        .zip(fields.iter())
+        .map(|((name, field_type), field)| {
+            let vis = &field.vis;
             quote! {
-                #name: <#field_type as axiom_circuit::input::raw_input::RawInput<axiom_sdk::Fr>>::FEType<T>,
+                #vis #name: <#field_type as axiom_circuit::input::raw_input::RawInput<axiom_sdk::Fr>>::FEType<T>,
             }
         })
         .collect();
@@ -64,16 +195,18 @@ pub fn impl_new_struct(ast: &ItemStruct) -> Result<TokenStream, TokenStream> {
     })
 }
 
-pub fn impl_flatten_and_raw_input(ast: &DeriveInput) -> TokenStream {
+/// Implements `InputFlatten` for the circuit struct `ast`, and `RawInput` for its native struct
+/// `raw_circuit_name_ident`, constraining its assigned inputs with `constrain` (see
+/// `impl_constrain`). If `compute_input` is set, the native struct is also made an
+/// `AxiomComputeInput` with these additional items.
+pub fn impl_flatten_and_raw_input(
+    ast: &DeriveInput,
+    raw_circuit_name_ident: &Ident,
+    constrain: &TokenStream,
+    compute_input: Option<&TokenStream>,
+) -> TokenStream {
     let name = &ast.ident;
 
-    let raw_circuit_name = name
-        .to_string()
-        .trim_end_matches("CircuitInput")
-        .to_string()
-        + "Input";
-    let raw_circuit_name_ident = Ident::new(&raw_circuit_name, Span::call_site());
-
     let fields = match ast.data {
         Data::Struct(ref data_struct) => &data_struct.fields,
         _ => {
@@ -160,7 +293,7 @@ pub fn impl_flatten_and_raw_input(ast: &DeriveInput) -> TokenStream {
         })
         .collect();
 
-    quote! {
+    let impls = quote! {
         impl #impl_generics axiom_circuit::input::flatten::InputFlatten<T> for #name #ty_generics {
             const NUM_FE: usize = #(#num_fe_tokens + )* 0;
             fn flatten_vec(&self) -> Vec<T> {
@@ -193,13 +326,22 @@ pub fn impl_flatten_and_raw_input(ast: &DeriveInput) -> TokenStream {
         }
 
         impl #new_impl_generics axiom_circuit::input::raw_input::RawInput<F> for #raw_circuit_name_ident #old_ty_generics {
-            type FEType<T: Copy> = #name #new_ty_generics;
+            type FEType<T: Copy> = #name #ty_generics;
             fn convert(&self) -> Self::FEType<F> {
                 use axiom_circuit::input::raw_input::RawInput;
                 #name {
                     #(#field_names: self.#field_names.convert(),)*
                 }
             }
+            #[allow(unused_variables)]
+            fn constrain(
+                ctx: &mut axiom_circuit::axiom_eth::halo2_base::Context<F>,
+                range: &axiom_circuit::axiom_eth::halo2_base::gates::RangeChip<F>,
+                witness: &Self::FEType<axiom_circuit::axiom_eth::halo2_base::AssignedValue<F>>,
+            ) {
+                use axiom_circuit::axiom_eth::halo2_base::gates::RangeInstructions;
+                #constrain
+            }
         }
 
         impl #new_impl_generics From<#raw_circuit_name_ident #old_ty_generics> for #name #new_ty_generics {
@@ -218,10 +360,79 @@ pub fn impl_flatten_and_raw_input(ast: &DeriveInput) -> TokenStream {
             }
         }
 
-        impl #old_impl_generics axiom_sdk::axiom::AxiomComputeInput for #raw_circuit_name_ident #old_ty_generics {
-            type LogicInput = #raw_circuit_name_ident #old_ty_generics;
-            type Input<T: Copy> = #name #ty_generics;
+    };
+    let compute_input_impl = compute_input.map(|compute_input| {
+        quote! {
+            impl #old_impl_generics axiom_sdk::axiom::AxiomComputeInput for #raw_circuit_name_ident #old_ty_generics {
+                type LogicInput = #raw_circuit_name_ident #old_ty_generics;
+                type Input<T: Copy> = #name #ty_generics;
+                fn constrain_inputs(
+                    ctx: &mut axiom_circuit::axiom_eth::halo2_base::Context<axiom_sdk::Fr>,
+                    range: &axiom_circuit::axiom_eth::halo2_base::gates::RangeChip<axiom_sdk::Fr>,
+                    assigned_inputs: &Self::Input<axiom_circuit::axiom_eth::halo2_base::AssignedValue<axiom_sdk::Fr>>,
+                ) {
+                    <Self as axiom_circuit::input::raw_input::RawInput<axiom_sdk::Fr>>::constrain(ctx, range, assigned_inputs);
+                }
+                #compute_input
+            }
         }
+    });
+    quote! {
+        #impls
+        #compute_input_impl
+    }
+}
+
+/// Implements `RawInput` for a fieldless enum, which is input as its discriminant
+pub fn impl_enum_raw_input(ast: &DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let variants = match ast.data {
+        Data::Enum(ref data_enum) => &data_enum.variants,
+        _ => unreachable!(),
+    };
+    if !ast.generics.params.is_empty() {
+        return quote! {
+            compile_error!("AxiomInput macro does not support generic enums");
+        };
+    }
+    if variants
+        .iter()
+        .any(|variant| !matches!(variant.fields, Fields::Unit))
+    {
+        return quote! {
+            compile_error!("AxiomInput macro only supports enums without fields");
+        };
+    }
+    let variant_names: Vec<_> = variants.iter().map(|variant| &variant.ident).collect();
 
+    quote! {
+        impl<F: axiom_circuit::axiom_eth::Field> axiom_circuit::input::raw_input::RawInput<F> for #name {
+            type FEType<T: Copy> = T;
+            fn convert(&self) -> Self::FEType<F> {
+                let discriminant: u64 = match self {
+                    #(Self::#variant_names => Self::#variant_names as u64,)*
+                };
+                F::from(discriminant)
+            }
+            fn constrain(
+                ctx: &mut axiom_circuit::axiom_eth::halo2_base::Context<F>,
+                range: &axiom_circuit::axiom_eth::halo2_base::gates::RangeChip<F>,
+                witness: &axiom_circuit::axiom_eth::halo2_base::AssignedValue<F>,
+            ) {
+                use axiom_circuit::axiom_eth::halo2_base::{
+                    gates::{GateInstructions, RangeInstructions},
+                    QuantumCell::Constant,
+                };
+                let gate = range.gate();
+                // the discriminant is that of a variant iff the product of its differences to
+                // the discriminants of all variants is zero
+                let mut product = ctx.load_constant(F::ONE);
+                for discriminant in [#(Self::#variant_names as u64),*] {
+                    let diff = gate.sub(ctx, *witness, Constant(F::from(discriminant)));
+                    product = gate.mul(ctx, product, diff);
+                }
+                gate.assert_is_const(ctx, &product, &F::ZERO);
+            }
+        }
     }
 }
@@ -1,6 +1,12 @@
-use input::{impl_flatten_and_raw_input, impl_new_struct};
+use input::{
+    impl_constrain, impl_enum_raw_input, impl_flatten_and_raw_input, impl_new_struct,
+    impl_public_inputs, strip_field_attrs, InputArgs,
+};
+use output::impl_compute_output;
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput, FnArg, Ident, ItemFn, ItemStruct, Type, Visibility};
+use syn::{
+    parse_macro_input, Data, DeriveInput, FnArg, Ident, ItemFn, ItemStruct, Type, Visibility,
+};
 
 extern crate proc_macro;
 extern crate syn;
@@ -8,25 +14,43 @@ extern crate syn;
 extern crate quote;
 
 mod input;
+mod output;
 
 #[proc_macro_attribute]
 #[allow(non_snake_case)]
 /// Derive the `AxiomComputeInput` trait for a struct.
-/// The struct must be named `_Input`. Ex: `ExampleInput`.
+/// The struct must be named `_Input`. Ex: `ExampleInput`, unless the circuit struct is named with
+/// `#[AxiomComputeInput(circuit = ExampleAssigned)]`.
 /// All the fields of the struct must implement `RawInput` from `axiom_circuit::input::raw_input`,
-/// which has already been implemented for most primitive Rust types and Ethers types.
-pub fn AxiomComputeInput(_args: TokenStream, input: TokenStream) -> TokenStream {
+/// which has already been implemented for most primitive Rust types and Ethers types, and is
+/// implemented for structs and enums with `#[derive(AxiomInput)]`.
+/// A field can be constrained before `compute` runs with `#[range(bits = N)]` or `#[max(N)]`;
+/// the types that need it (ex: `Address`) are constrained automatically.
+/// A field marked `#[public]` is exposed as a public instance of the circuit.
+pub fn AxiomComputeInput(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut input_args = InputArgs::default();
+    let args_parser = syn::meta::parser(|meta| input_args.parse(meta));
+    parse_macro_input!(args with args_parser);
     let input_clone = input.clone();
-    let ast = parse_macro_input!(input_clone as ItemStruct);
-    // let name = ast.ident.clone();
-    let new_struct = impl_new_struct(&ast);
-    if let Err(err) = new_struct {
-        return err.into();
-    }
-    let new_struct = new_struct.unwrap();
+    let mut ast = parse_macro_input!(input_clone as ItemStruct);
+    let constrain = match impl_constrain(&ast) {
+        Ok(constrain) => constrain,
+        Err(err) => return err.into(),
+    };
+    let public_inputs = impl_public_inputs(&ast);
+    strip_field_attrs(&mut ast);
+    let circuit_name = match input_args.circuit_name(&ast.ident, true) {
+        Ok(circuit_name) => circuit_name,
+        Err(err) => return err.into(),
+    };
+    let new_struct = match impl_new_struct(&ast, &circuit_name) {
+        Ok(new_struct) => new_struct,
+        Err(err) => return err.into(),
+    };
     let new_derive_input: TokenStream = new_struct.clone().into();
     let new_ast = parse_macro_input!(new_derive_input as DeriveInput);
-    let flatten = impl_flatten_and_raw_input(&new_ast);
+    let flatten =
+        impl_flatten_and_raw_input(&new_ast, &ast.ident, &constrain, Some(&public_inputs));
     quote! {
         #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
         #ast
@@ -36,6 +60,72 @@ pub fn AxiomComputeInput(_args: TokenStream, input: TokenStream) -> TokenStream
     .into()
 }
 
+#[proc_macro_derive(AxiomInput, attributes(axiom_input, range, max))]
+/// Derive `RawInput` from `axiom_circuit::input::raw_input` for a struct or a fieldless enum, so
+/// it can be used as a field of an `AxiomComputeInput` or of another `AxiomInput` struct.
+///
+/// For a struct, a circuit struct with the same fields is generated, named like the struct with
+/// its `Input` suffix (if any) replaced with `CircuitInput`, or named with
+/// `#[axiom_input(circuit = ExampleAssigned)]`. The fields must implement `RawInput`, and the
+/// struct must implement `Default`. Fields accept the `#[range(bits = N)]` and `#[max(N)]`
+/// attributes of `#[AxiomComputeInput]`.
+///
+/// A fieldless enum is input as its discriminant, which is constrained to be that of a variant.
+pub fn axiom_input(input: TokenStream) -> TokenStream {
+    let input_clone = input.clone();
+    let derive_ast = parse_macro_input!(input_clone as DeriveInput);
+    if let Data::Enum(_) = derive_ast.data {
+        return impl_enum_raw_input(&derive_ast).into();
+    }
+    let input_args = match InputArgs::from_attrs(&derive_ast.attrs) {
+        Ok(input_args) => input_args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let ast = parse_macro_input!(input as ItemStruct);
+    let constrain = match impl_constrain(&ast) {
+        Ok(constrain) => constrain,
+        Err(err) => return err.into(),
+    };
+    let circuit_name = match input_args.circuit_name(&ast.ident, false) {
+        Ok(circuit_name) => circuit_name,
+        Err(err) => return err.into(),
+    };
+    let new_struct = match impl_new_struct(&ast, &circuit_name) {
+        Ok(new_struct) => new_struct,
+        Err(err) => return err.into(),
+    };
+    let new_derive_input: TokenStream = new_struct.clone().into();
+    let new_ast = parse_macro_input!(new_derive_input as DeriveInput);
+    let flatten = impl_flatten_and_raw_input(&new_ast, &ast.ident, &constrain, None);
+    quote! {
+        #new_struct
+        #flatten
+    }
+    .into()
+}
+
+#[proc_macro_attribute]
+#[allow(non_snake_case)]
+/// Derive the `AxiomComputeOutput` trait for a struct.
+/// The struct must be named `_Output`. Ex: `ExampleOutput`.
+/// All the fields of the struct must implement `RawOutput` from `axiom_circuit::output::raw_output`,
+/// which has already been implemented for `bool`, unsigned integers, `Address`, `U256` and `H256`.
+/// Each field is returned as one result, in order.
+pub fn AxiomComputeOutput(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let input_clone = input.clone();
+    let ast = parse_macro_input!(input_clone as ItemStruct);
+    let compute_output = match impl_compute_output(&ast) {
+        Ok(compute_output) => compute_output,
+        Err(err) => return err.into(),
+    };
+    quote! {
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        #ast
+        #compute_output
+    }
+    .into()
+}
+
 #[proc_macro_attribute]
 #[allow(non_snake_case)]
 pub fn AxiomCompute(_attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -0,0 +1,105 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::ToTokens;
+use syn::ItemStruct;
+
+/// Converts a snake case field name to the camel case name of its Solidity local
+fn to_camel_case(name: &str) -> String {
+    let mut camel = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' && !camel.is_empty() {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+pub fn impl_compute_output(ast: &ItemStruct) -> Result<TokenStream, TokenStream> {
+    let name = &ast.ident;
+
+    if !name.to_string().ends_with("Output") {
+        return Err(quote! {
+            compile_error!("Struct must be named `_Output`. Ex: `ExampleOutput`");
+        });
+    }
+    if !ast.generics.params.is_empty() {
+        return Err(quote! {
+            compile_error!("AxiomComputeOutput macro does not support generics");
+        });
+    }
+    let name_prefix = name.to_string().trim_end_matches("Output").to_string();
+    let circuit_output_name = format!("{}CircuitOutput", name_prefix);
+    let circuit_output_name_ident = Ident::new(&circuit_output_name, Span::call_site());
+
+    let field_types: Vec<_> = ast
+        .fields
+        .iter()
+        .map(|field| field.ty.to_token_stream())
+        .collect();
+
+    let mut field_names = vec![];
+    for field in &ast.fields {
+        match field.ident {
+            Some(ref ident) => field_names.push(ident.clone()),
+            None => {
+                return Err(quote! {
+                    compile_error!("AxiomComputeOutput macro only supports named fields");
+                })
+            }
+        }
+    }
+    let solidity_names: Vec<_> = field_names
+        .iter()
+        .map(|name| to_camel_case(&name.to_string()))
+        .collect();
+    let indices = 0..field_names.len();
+    let num_results = field_names.len();
+
+    Ok(quote! {
+        #[derive(Debug, Clone)]
+        pub struct #circuit_output_name_ident {
+            #(pub #field_names: <#field_types as axiom_circuit::output::raw_output::RawOutput<axiom_sdk::Fr>>::Assigned,)*
+        }
+
+        impl axiom_sdk::axiom::AxiomComputeOutput for #name {
+            type CircuitOutput = #circuit_output_name_ident;
+
+            fn results(
+                api: &mut axiom_sdk::axiom::AxiomAPI,
+                output: Self::CircuitOutput,
+            ) -> Vec<axiom_sdk::axiom::AxiomResult> {
+                use axiom_circuit::output::raw_output::RawOutput;
+                let range = api.range;
+                vec![
+                    #(<#field_types as RawOutput<axiom_sdk::Fr>>::constrain(api.ctx(), range, output.#field_names).into(),)*
+                ]
+            }
+
+            fn decode(compute_results: &[axiom_sdk::ethers::types::H256]) -> anyhow::Result<Self> {
+                use axiom_circuit::output::raw_output::RawOutput;
+                if compute_results.len() != #num_results {
+                    anyhow::bail!(
+                        "Invalid number of compute results: {} != {}",
+                        compute_results.len(),
+                        #num_results
+                    );
+                }
+                Ok(#name {
+                    #(#field_names: <#field_types as RawOutput<axiom_sdk::Fr>>::decode(compute_results[#indices])?,)*
+                })
+            }
+
+            fn solidity_results() -> Vec<axiom_sdk::solidity::SolidityResult> {
+                use axiom_circuit::output::raw_output::RawOutput;
+                vec![
+                    #(axiom_sdk::solidity::SolidityResult::new(#solidity_names, <#field_types as RawOutput<axiom_sdk::Fr>>::SOLIDITY_TYPE),)*
+                ]
+            }
+        }
+    })
+}
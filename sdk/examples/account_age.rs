@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 
 use axiom_sdk::{
-    axiom::{AxiomAPI, AxiomComputeFn, AxiomComputeInput, AxiomResult},
+    axiom::{AxiomAPI, AxiomComputeFn, AxiomComputeInput, AxiomComputeOutput},
     cmd::run_cli,
     ethers::types::Address,
     halo2_base::{
@@ -14,15 +14,24 @@ use axiom_sdk::{
 
 #[AxiomComputeInput]
 pub struct AccountAgeInput {
+    pub addr: Address,
+    #[range(bits = 64)]
+    pub claimed_block_number: u64,
+}
+
+#[AxiomComputeOutput]
+pub struct AccountAgeOutput {
     pub addr: Address,
     pub claimed_block_number: u64,
 }
 
 impl AxiomComputeFn for AccountAgeInput {
+    type Output = AccountAgeOutput;
+
     fn compute(
         api: &mut AxiomAPI,
         assigned_inputs: AccountAgeCircuitInput<AssignedValue<Fr>>,
-    ) -> Vec<AxiomResult> {
+    ) -> AccountAgeCircuitOutput {
         let zero = api.ctx().load_zero();
         let one = api.ctx().load_constant(Fr::one());
         let prev_block = api
@@ -41,10 +50,10 @@ impl AxiomComputeFn for AccountAgeInput {
 
         api.range.check_less_than(api.ctx(), zero, curr_nonce, 40);
 
-        vec![
-            assigned_inputs.addr.into(),
-            assigned_inputs.claimed_block_number.into(),
-        ]
+        AccountAgeCircuitOutput {
+            addr: assigned_inputs.addr,
+            claimed_block_number: assigned_inputs.claimed_block_number,
+        }
     }
 }
 
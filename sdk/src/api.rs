@@ -8,10 +8,9 @@ use axiom_circuit::{
         rlc::circuit::builder::RlcCircuitBuilder,
         utils::uint_to_bytes_be,
     },
-    subquery::caller::SubqueryCaller,
+    subquery::{caller::SubqueryCaller, source::SharedDataSource},
     utils::{from_hi_lo, to_hi_lo},
 };
-use ethers::providers::Http;
 
 use crate::{
     subquery::{
@@ -32,14 +31,14 @@ pub struct AxiomAPI<'a> {
     /// The main chip for ZK primitives
     pub range: &'a RangeChip<Fr>,
     /// The struct that manages all subquery calls
-    subquery_caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    subquery_caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
 }
 
 impl<'a> AxiomAPI<'a> {
     pub fn new(
         builder: &'a mut RlcCircuitBuilder<Fr>,
         range: &'a RangeChip<Fr>,
-        subquery_caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+        subquery_caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
     ) -> Self {
         Self {
             builder,
@@ -49,7 +48,7 @@ impl<'a> AxiomAPI<'a> {
     }
 
     /// Returns a thread-safe [SubqueryCaller] object.
-    pub fn subquery_caller(&self) -> Arc<Mutex<SubqueryCaller<Http, Fr>>> {
+    pub fn subquery_caller(&self) -> Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>> {
         self.subquery_caller.clone()
     }
 
@@ -172,6 +171,34 @@ impl<'a> AxiomAPI<'a> {
         get_tx(ctx, self.subquery_caller.clone(), block_number, tx_idx)
     }
 
+    /// Exposes `value` as a public instance of the circuit, in a second instance column.
+    ///
+    /// The number of values that can be exposed is set by `AxiomComputeFn::MAX_PUBLIC_INSTANCES`.
+    /// The AxiomV2Query contract verifies circuits with a single instance column, so such a
+    /// circuit can only be verified off-chain.
+    pub fn expose_public(&mut self, value: AssignedValue<Fr>) {
+        self.subquery_caller.lock().unwrap().expose_public(value);
+    }
+
+    /// Runs `f` as a named region, so that the cells it uses are reported separately by `stats`.
+    ///
+    /// Regions can be nested, and the calls of a region with the same name are added up.
+    ///
+    /// * `name` - The name of the region.
+    /// * `f` - The closure to run with this `AxiomAPI`.
+    pub fn region<R>(&mut self, name: &str, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.subquery_caller
+            .lock()
+            .unwrap()
+            .enter_region(name, self.builder);
+        let result = f(self);
+        self.subquery_caller
+            .lock()
+            .unwrap()
+            .exit_region(self.builder);
+        result
+    }
+
     pub fn keccak_fix_len(&mut self, bytes: Vec<AssignedValue<Fr>>) -> HiLo<AssignedValue<Fr>> {
         let ctx = self.builder.base.main(0);
         let subquery_caller = self.subquery_caller.clone();
@@ -4,8 +4,10 @@ use std::{
     fs::{self, File},
     io::BufWriter,
     path::PathBuf,
+    sync::Arc,
 };
 
+use anyhow::{Context, Result};
 use axiom_circuit::{
     axiom_eth::{
         halo2_base::{gates::circuit::BaseCircuitParams, AssignedValue},
@@ -14,21 +16,38 @@ use axiom_circuit::{
         rlc::circuit::RlcCircuitParams,
         utils::keccak::decorator::RlcKeccakCircuitParams,
     },
+    params::ParamsObjective,
+    query::{
+        build_send_query_tx, encode_send_query_calldata, AxiomV2Callback, AxiomV2FeeData,
+        SendQueryArgs, DEFAULT_CALLBACK_GAS_LIMIT, DEFAULT_MAX_FEE_PER_GAS,
+    },
     scaffold::AxiomCircuit,
-    types::{AxiomCircuitParams, AxiomCircuitPinning},
+    subquery::{
+        cache::{CachedDataSource, SubqueryCacheMode},
+        caller::SubqueryConfig,
+        mock::{MockFixture, MockStrategy},
+        source::{JsonFixtureDataSource, SharedDataSource},
+        verify::ProofVerifyingProvider,
+    },
+    types::{AxiomCircuitParams, AxiomCircuitPinning, AxiomV2RunOutput},
+    utils::build_axiom_v2_query_data,
 };
 pub use clap::Parser;
-use clap::Subcommand;
-use ethers::providers::{Http, Provider};
-use log::warn;
+use clap::{Args, Subcommand};
+use ethers::{
+    providers::{Http, Middleware, Provider},
+    types::{Address, Bytes, H256},
+};
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
+use tokio::runtime::Runtime;
 
 use crate::{
-    compute::{AxiomCompute, AxiomComputeFn},
+    compute::{AxiomCompute, AxiomComputeFn, AxiomComputeOutput},
     Fr,
 };
 
-#[derive(Clone, Copy, Debug, Subcommand)]
+#[derive(Clone, Debug, Subcommand)]
 /// Circuit CLI commands
 pub enum SnarkCmd {
     /// Run the mock prover
@@ -39,6 +58,110 @@ pub enum SnarkCmd {
     Prove,
     /// Generate an Axiom compute query
     Run,
+    /// Generate a `sendQuery` transaction for the output of `run`
+    SendQuery(SendQueryCmd),
+    /// Fit the circuit to a range of degrees and recommend one
+    SearchParams(SearchParamsCmd),
+    /// Report the cells, constraints and subqueries used by the circuit
+    Stats,
+    /// Write a Solidity callback contract for the circuit of `keygen`
+    Solidity(SolidityCmd),
+}
+
+#[derive(Args, Clone, Debug)]
+/// Arguments of the `search-params` command
+pub struct SearchParamsCmd {
+    #[arg(long = "min-k", default_value_t = 10, help = "Smallest degree to try")]
+    /// The smallest degree to try
+    pub min_k: usize,
+    #[arg(long = "max-k", default_value_t = 20, help = "Largest degree to try")]
+    /// The largest degree to try
+    pub max_k: usize,
+    #[arg(
+        long = "objective",
+        default_value = "min-k",
+        help = "Whether to pick the smallest feasible degree (min-k) or the cheapest one to prove, estimated as columns * 2^k (min-cost)"
+    )]
+    /// What to pick the degree by
+    pub objective: ParamsObjective,
+}
+
+#[derive(Args, Clone, Debug)]
+/// Arguments of the `solidity` command
+pub struct SolidityCmd {
+    #[arg(
+        long = "contract-name",
+        default_value = "AxiomCallback",
+        help = "Name of the generated contract"
+    )]
+    /// The name of the generated contract
+    pub contract_name: String,
+}
+
+#[derive(Args, Clone, Debug)]
+/// Arguments of the `send-query` command
+pub struct SendQueryCmd {
+    #[arg(long = "query-address", help = "Address of the AxiomV2Query contract")]
+    /// The address of the AxiomV2Query contract
+    pub query_address: Address,
+    #[arg(
+        long = "callback-target",
+        help = "Contract called back with the query results"
+    )]
+    /// The contract called back with the query results
+    pub callback_target: Address,
+    #[arg(
+        long = "callback-extra-data",
+        default_value = "0x",
+        help = "Hex bytes passed along to the callback"
+    )]
+    /// The extra data passed along to the callback
+    pub callback_extra_data: Bytes,
+    #[arg(
+        long = "refundee",
+        help = "Address refunded the unused part of the payment"
+    )]
+    /// The address refunded the unused part of the payment
+    pub refundee: Address,
+    #[arg(long = "max-fee-per-gas", default_value_t = DEFAULT_MAX_FEE_PER_GAS, help = "Maximum fee per gas of the callback in wei")]
+    /// The maximum fee per gas of the callback
+    pub max_fee_per_gas: u64,
+    #[arg(long = "callback-gas-limit", default_value_t = DEFAULT_CALLBACK_GAS_LIMIT, help = "Gas limit of the callback")]
+    /// The gas limit of the callback
+    pub callback_gas_limit: u32,
+    #[arg(
+        long = "override-axiom-query-fee",
+        default_value_t = 0,
+        help = "Query fee in wei to pay in place of the contract's, if non-zero"
+    )]
+    /// The query fee to pay in place of the one set on the contract, if non-zero
+    pub override_axiom_query_fee: u64,
+    #[arg(
+        long = "user-salt",
+        help = "Salt to tell apart otherwise identical queries [default: 0x0]"
+    )]
+    /// The salt to tell apart otherwise identical queries
+    pub user_salt: Option<H256>,
+    #[arg(
+        long = "payment",
+        default_value_t = 0,
+        help = "Wei sent along with the query"
+    )]
+    /// The wei sent along with the query
+    pub payment: u128,
+    #[arg(
+        long = "submit",
+        help = "Send the transaction from an unlocked account of the JSON RPC provider, e.g. a local anvil node"
+    )]
+    /// Whether to send the transaction through the JSON RPC provider
+    pub submit: bool,
+    #[arg(
+        long = "from",
+        requires = "submit",
+        help = "Unlocked account to send the transaction from [default: the first account of the provider]"
+    )]
+    /// The account to send the transaction from
+    pub from: Option<Address>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -60,6 +183,10 @@ impl std::fmt::Display for SnarkCmd {
             Self::Keygen => write!(f, "keygen"),
             Self::Prove => write!(f, "prove"),
             Self::Run => write!(f, "run"),
+            Self::SendQuery(_) => write!(f, "send-query"),
+            Self::SearchParams(_) => write!(f, "search-params"),
+            Self::Stats => write!(f, "stats"),
+            Self::Solidity(_) => write!(f, "solidity"),
         }
     }
 }
@@ -99,6 +226,179 @@ pub struct Cli {
     )]
     /// The path to a custom circuit configuration
     pub config: Option<PathBuf>,
+    #[arg(
+        long = "auto-config",
+        help = "Fit the circuit columns and lookup bits to the degree with witness generation passes, for mock and keygen (optional)"
+    )]
+    /// Whether to fit the circuit params to the degree, keeping the circuit type of the config,
+    /// for the `mock` and `keygen` commands
+    pub auto_config: bool,
+    #[arg(
+        long = "fixture",
+        help = "JSON subquery fixture to use instead of a JSON RPC provider (optional)"
+    )]
+    /// The path to a JSON subquery fixture
+    pub fixture: Option<PathBuf>,
+    #[arg(
+        long = "source-chain-id",
+        value_name = "CHAIN_ID",
+        help = "Chain ID the query is made on, if not that of the JSON RPC provider (optional)"
+    )]
+    /// The chain ID of the query written to `output.json`, defaults to that of the provider
+    pub source_chain_id: Option<u64>,
+    #[arg(
+        long = "subquery-cache",
+        value_name = "MODE",
+        default_value = "use",
+        help = "Whether to use, refresh or bypass the subquery cache under the data path"
+    )]
+    /// How to use the on-disk subquery result cache
+    pub subquery_cache: SubqueryCacheMode,
+    #[arg(
+        long = "prefetch",
+        value_name = "PARALLELISM",
+        help = "Fetch all subqueries before witness generation with up to this many concurrent requests (optional)"
+    )]
+    /// The maximum number of concurrent requests when prefetching subqueries. Subqueries that depend
+    /// on earlier results are found by running `compute` on placeholder values, given by
+    /// `--mock-seed` or `--mock-fixture`, until those results are fetched.
+    pub prefetch: Option<usize>,
+    #[arg(
+        long = "verify-proofs",
+        help = "Verify subquery results against the roots of their block before proving"
+    )]
+    /// Whether to verify subquery results natively before proving
+    pub verify_proofs: bool,
+    #[arg(
+        long = "mock-seed",
+        value_name = "SEED",
+        conflicts_with = "mock_fixture",
+        help = "Answer subqueries with pseudo-random values from this seed during keygen (optional)"
+    )]
+    /// The seed of pseudo-random subquery results when running without inputs
+    pub mock_seed: Option<u64>,
+    #[arg(
+        long = "mock-fixture",
+        value_name = "FIXTURE",
+        help = "JSON subquery fixture of a sample run to answer subqueries with during keygen (optional)"
+    )]
+    /// The path to a JSON subquery fixture to take subquery results from when running without inputs
+    pub mock_fixture: Option<PathBuf>,
+}
+
+fn get_cli_provider(provider: Option<String>) -> Provider<Http> {
+    let provider_uri = provider
+        .unwrap_or_else(|| env::var("PROVIDER_URI").expect("The `provider` argument is required for the selected command. Either pass it as an argument or set the `PROVIDER_URI` environment variable."));
+    Provider::<Http>::try_from(provider_uri).unwrap()
+}
+
+/// Decodes the compute results to the `Output` type of the compute function, or returns `None` if
+/// it declares no results
+fn decode_compute_output<A: AxiomComputeFn>(
+    compute_results: &[H256],
+) -> Result<Option<serde_json::Value>> {
+    let compute_output = A::Output::decode(compute_results)
+        .and_then(|compute_output| Ok(serde_json::to_value(compute_output)?))
+        .context("Decoding the compute results to the output type failed")?;
+    Ok(Some(compute_output).filter(|value| !value.is_null()))
+}
+
+/// Writes the `sendQuery` transaction for `output.json` to `send_query_tx.json`, and sends it if
+/// `--submit` is given
+fn send_query(cli: &Cli, cmd: &SendQueryCmd) {
+    let data_path = cli
+        .data_path
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("data"));
+    let output_json_path = data_path.join(PathBuf::from("output.json"));
+    let f = File::open(&output_json_path).unwrap_or_else(|_| {
+        panic!("Could not open {output_json_path:?}, generate it with the `run` command first")
+    });
+    let output: AxiomV2RunOutput = serde_json::from_reader(f).expect("Unable to parse output.json");
+    let source_chain_id = cli
+        .source_chain_id
+        .or(output.query.map(|query| query.source_chain_id))
+        .expect("output.json has no source chain id, pass it with the `source-chain-id` argument");
+    let args = SendQueryArgs {
+        source_chain_id,
+        callback: AxiomV2Callback {
+            target: cmd.callback_target,
+            extra_data: cmd.callback_extra_data.clone(),
+        },
+        fee_data: AxiomV2FeeData {
+            max_fee_per_gas: cmd.max_fee_per_gas,
+            callback_gas_limit: cmd.callback_gas_limit,
+            override_axiom_query_fee: cmd.override_axiom_query_fee,
+        },
+        user_salt: cmd.user_salt.unwrap_or_default(),
+        refundee: cmd.refundee,
+    };
+    let calldata = encode_send_query_calldata(&output.compute_query, &output.data, &args)
+        .unwrap_or_else(|e| panic!("Could not build the sendQuery calldata: {e}"));
+    let mut tx = build_send_query_tx(cmd.query_address, cmd.payment.into(), calldata);
+    let tx_path = data_path.join(PathBuf::from("send_query_tx.json"));
+    let f =
+        File::create(&tx_path).unwrap_or_else(|_| panic!("Could not create file at {tx_path:?}"));
+    serde_json::to_writer_pretty(f, &tx).expect("Writing transaction should not fail");
+    if !cmd.submit {
+        return;
+    }
+    let provider = get_cli_provider(cli.provider.clone());
+    let rt = Runtime::new().unwrap();
+    let from = cmd.from.unwrap_or_else(|| {
+        let accounts = rt
+            .block_on(provider.get_accounts())
+            .expect("Unable to fetch accounts from provider");
+        *accounts
+            .first()
+            .expect("The provider has no unlocked accounts")
+    });
+    tx.set_from(from);
+    let receipt = rt
+        .block_on(async { provider.send_transaction(tx, None).await?.await })
+        .unwrap_or_else(|e| panic!("Sending the transaction failed: {e}"))
+        .expect("The transaction was dropped");
+    if receipt.status != Some(1.into()) {
+        panic!(
+            "sendQuery transaction {:?} reverted",
+            receipt.transaction_hash
+        );
+    }
+    info!("Sent sendQuery transaction {:?}", receipt.transaction_hash);
+}
+
+/// Writes a Solidity callback contract for the circuit of `pk.bin` and `pinning.json` to
+/// `<contract name>.sol`
+fn write_solidity_callback<A: AxiomComputeFn>(cli: &Cli, cmd: &SolidityCmd)
+where
+    A::Input<Fr>: Default + Debug,
+    A::Input<AssignedValue<Fr>>: Debug,
+{
+    let data_path = cli
+        .data_path
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("data"));
+    let pinning_path = data_path.join(PathBuf::from("pinning.json"));
+    let f = File::open(&pinning_path).unwrap_or_else(|_| {
+        panic!("Could not open {pinning_path:?}, generate it with the `keygen` command first")
+    });
+    let pinning: AxiomCircuitPinning = serde_json::from_reader(f).unwrap();
+    let pk_path = data_path.join(PathBuf::from("pk.bin"));
+    let mut f = File::open(pk_path).unwrap();
+    let pk =
+        ProvingKey::<G1Affine>::read::<_, AxiomCircuit<Fr, SharedDataSource, AxiomCompute<A>>>(
+            &mut f,
+            SerdeFormat::RawBytes,
+            pinning.params.clone(),
+        )
+        .unwrap();
+    let source = AxiomCompute::<A>::new()
+        .use_pinning(pinning)
+        .solidity_callback(&cmd.contract_name, pk.get_vk())
+        .unwrap_or_else(|e| panic!("Could not generate the Solidity callback: {e}"));
+    let sol_path = data_path.join(PathBuf::from(format!("{}.sol", cmd.contract_name)));
+    fs::write(&sol_path, source)
+        .unwrap_or_else(|_| panic!("Could not create file at {sol_path:?}"));
 }
 
 /// Runs the CLI given on any struct that implements the `AxiomComputeFn` trait
@@ -108,86 +408,205 @@ where
     A::Input<AssignedValue<Fr>>: Debug,
 {
     let cli = Cli::parse();
+    if let SnarkCmd::SendQuery(cmd) = &cli.command {
+        send_query(&cli, cmd);
+        return;
+    }
+    if let SnarkCmd::Solidity(cmd) = &cli.command {
+        write_solidity_callback::<A>(&cli, cmd);
+        return;
+    }
     match cli.command {
-        SnarkCmd::Mock | SnarkCmd::Prove | SnarkCmd::Run => {
+        SnarkCmd::Mock
+        | SnarkCmd::Prove
+        | SnarkCmd::Run
+        | SnarkCmd::SearchParams(_)
+        | SnarkCmd::Stats => {
             if cli.input_path.is_none() {
                 panic!("The `input_path` argument is required for the selected command.");
             }
         }
         _ => {}
     }
-    match cli.command {
-        SnarkCmd::Mock | SnarkCmd::Keygen => {
-            if cli.degree.is_none() {
-                panic!("The `degree` argument is required for the selected command.");
+    // the degree of the params the command starts from, search-params tries its own degrees
+    let degree = match &cli.command {
+        SnarkCmd::Mock | SnarkCmd::Keygen | SnarkCmd::Stats => Some(
+            cli.degree
+                .expect("The `degree` argument is required for the selected command.")
+                as usize,
+        ),
+        SnarkCmd::SearchParams(cmd) => {
+            if cli.degree.is_some() {
+                warn!("The `degree` argument is not used for the selected command.");
             }
+            Some(cmd.min_k)
         }
         _ => {
             if cli.degree.is_some() {
                 warn!("The `degree` argument is not used for the selected command.");
             }
+            None
         }
+    };
+    if cli.auto_config && !matches!(cli.command, SnarkCmd::Mock | SnarkCmd::Keygen) {
+        panic!("The `auto-config` argument is only supported for the mock and keygen commands.");
     }
     let input_path = cli.input_path.unwrap();
     let json_str = fs::read_to_string(input_path).expect("Unable to read file");
     let input: A::LogicInput = serde_json::from_str(&json_str).expect("Unable to parse JSON");
-    let provider_uri = cli
-        .provider
-        .unwrap_or_else(|| env::var("PROVIDER_URI").expect("The `provider` argument is required for the selected command. Either pass it as an argument or set the `PROVIDER_URI` environment variable."));
-    let provider = Provider::<Http>::try_from(provider_uri).unwrap();
     let data_path = cli.data_path.unwrap_or_else(|| PathBuf::from("data"));
-
-    let params = if let Some(config) = cli.config {
-        let f = File::open(config).unwrap();
-        let raw_params: RawCircuitParams = serde_json::from_reader(f).unwrap();
-        let base_params = BaseCircuitParams {
-            k: raw_params.k,
-            num_advice_per_phase: raw_params.num_advice_per_phase,
-            num_fixed: raw_params.num_fixed,
-            num_lookup_advice_per_phase: raw_params.num_lookup_advice_per_phase,
-            lookup_bits: raw_params.lookup_bits,
-            num_instance_columns: 1,
+    let mut source_chain_id = cli.source_chain_id;
+    let data_source: SharedDataSource = if let Some(fixture) = cli.fixture {
+        Arc::new(JsonFixtureDataSource::from_path(fixture).expect("Unable to read fixture file"))
+    } else {
+        let provider = get_cli_provider(cli.provider);
+        let uncached: SharedDataSource = if cli.verify_proofs {
+            Arc::new(ProofVerifyingProvider::new(provider.clone()))
+        } else {
+            Arc::new(provider.clone())
         };
-        if let Some(keccak_rows_per_round) = raw_params.keccak_rows_per_round {
-            let rlc_columns = raw_params.num_rlc_columns.unwrap_or(0);
-            AxiomCircuitParams::Keccak(RlcKeccakCircuitParams {
-                keccak_rows_per_round,
-                rlc: RlcCircuitParams {
+        let chain_id = Runtime::new()
+            .unwrap()
+            .block_on(provider.get_chainid())
+            .expect("Unable to fetch chain id from provider");
+        source_chain_id.get_or_insert(chain_id.as_u64());
+        if cli.subquery_cache == SubqueryCacheMode::Bypass {
+            uncached
+        } else {
+            // cached results are only valid for the chain they were fetched from
+            let mut cache_dir = data_path
+                .join(PathBuf::from("subquery_cache"))
+                .join(chain_id.to_string());
+            // keep verified results apart so unverified ones are never served in their place
+            if cli.verify_proofs {
+                cache_dir = cache_dir.join("verified");
+            }
+            Arc::new(CachedDataSource::new(
+                uncached,
+                cache_dir,
+                cli.subquery_cache,
+            ))
+        }
+    };
+
+    // prove and run use the params of the keygen pinning
+    let params = degree.map(|degree| {
+        if let Some(config) = &cli.config {
+            let f = File::open(config).unwrap();
+            let raw_params: RawCircuitParams = serde_json::from_reader(f).unwrap();
+            let base_params = BaseCircuitParams {
+                k: raw_params.k,
+                num_advice_per_phase: raw_params.num_advice_per_phase,
+                num_fixed: raw_params.num_fixed,
+                num_lookup_advice_per_phase: raw_params.num_lookup_advice_per_phase,
+                lookup_bits: raw_params.lookup_bits,
+                num_instance_columns: 1,
+            };
+            if let Some(keccak_rows_per_round) = raw_params.keccak_rows_per_round {
+                let rlc_columns = raw_params.num_rlc_columns.unwrap_or(0);
+                AxiomCircuitParams::Keccak(RlcKeccakCircuitParams {
+                    keccak_rows_per_round,
+                    rlc: RlcCircuitParams {
+                        base: base_params,
+                        num_rlc_columns: rlc_columns,
+                    },
+                })
+            } else if let Some(rlc_columns) = raw_params.num_rlc_columns {
+                AxiomCircuitParams::Rlc(RlcCircuitParams {
                     base: base_params,
                     num_rlc_columns: rlc_columns,
-                },
-            })
-        } else if let Some(rlc_columns) = raw_params.num_rlc_columns {
-            AxiomCircuitParams::Rlc(RlcCircuitParams {
-                base: base_params,
-                num_rlc_columns: rlc_columns,
-            })
+                })
+            } else {
+                AxiomCircuitParams::Base(base_params)
+            }
         } else {
-            AxiomCircuitParams::Base(base_params)
+            AxiomCircuitParams::Base(BaseCircuitParams {
+                k: degree,
+                num_advice_per_phase: vec![4],
+                num_fixed: 1,
+                num_lookup_advice_per_phase: vec![1],
+                lookup_bits: Some(11),
+                num_instance_columns: 1,
+            })
         }
+    });
+
+    let mock_strategy = if let Some(fixture) = cli.mock_fixture {
+        MockStrategy::Fixture(
+            MockFixture::from_path(fixture).expect("Unable to read mock fixture file"),
+        )
+    } else if let Some(seed) = cli.mock_seed {
+        MockStrategy::Random { seed }
     } else {
-        AxiomCircuitParams::Base(BaseCircuitParams {
-            k: cli.degree.unwrap() as usize,
-            num_advice_per_phase: vec![4],
-            num_fixed: 1,
-            num_lookup_advice_per_phase: vec![1],
-            lookup_bits: Some(11),
-            num_instance_columns: 1,
-        })
+        MockStrategy::Zero
+    };
+    let subquery_config = SubqueryConfig {
+        prefetch_parallelism: cli.prefetch,
+        mock_strategy,
+        ..Default::default()
+    };
+
+    let params = match (params, degree) {
+        (Some(params), Some(degree))
+            if cli.auto_config && matches!(cli.command, SnarkCmd::Mock | SnarkCmd::Keygen) =>
+        {
+            let params = AxiomCompute::<A>::new()
+                .use_inputs(input.clone())
+                .use_params(params)
+                .use_data_source(data_source.clone())
+                .use_subquery_config(subquery_config.clone())
+                .auto_params(degree);
+            info!(
+                "Auto-configured circuit params:\n{}",
+                serde_json::to_string_pretty(&params).unwrap()
+            );
+            Some(params)
+        }
+        (params, _) => params,
     };
 
     match cli.command {
+        SnarkCmd::SearchParams(cmd) => {
+            let search = AxiomCompute::<A>::new()
+                .use_inputs(input)
+                .use_params(params.unwrap())
+                .use_data_source(data_source)
+                .use_subquery_config(subquery_config)
+                .search_params(cmd.min_k..=cmd.max_k, cmd.objective);
+            println!("{search}");
+            match search.best() {
+                Some(best) => println!(
+                    "Recommended ({}): -k {} --auto-config",
+                    cmd.objective, best.k
+                ),
+                None => println!(
+                    "No degree in {}..={} fits the circuit",
+                    cmd.min_k, cmd.max_k
+                ),
+            }
+        }
+        SnarkCmd::Stats => {
+            let stats = AxiomCompute::<A>::new()
+                .use_inputs(input)
+                .use_params(params.unwrap())
+                .use_data_source(data_source)
+                .use_subquery_config(subquery_config)
+                .stats();
+            println!("{stats}");
+        }
         SnarkCmd::Mock => {
-            AxiomCompute::<A>::new()
+            let compute = AxiomCompute::<A>::new()
                 .use_inputs(input)
-                .use_params(params)
-                .use_provider(provider)
-                .mock();
+                .use_params(params.unwrap())
+                .use_data_source(data_source)
+                .use_subquery_config(subquery_config);
+            compute.mock().unwrap_or_else(|e| panic!("{e}"));
         }
         SnarkCmd::Keygen => {
             let circuit = AxiomCompute::<A>::new()
-                .use_params(params)
-                .use_provider(provider);
+                .use_params(params.unwrap())
+                .use_data_source(data_source)
+                .use_subquery_config(subquery_config);
             let (_, pkey, pinning) = circuit.keygen();
             let pk_path = data_path.join(PathBuf::from("pk.bin"));
             if pk_path.exists() {
@@ -223,16 +642,19 @@ where
             let pinning: AxiomCircuitPinning = serde_json::from_reader(f).unwrap();
             let compute = AxiomCompute::<A>::new()
                 .use_pinning(pinning.clone())
-                .use_provider(provider);
+                .use_data_source(data_source)
+                .use_subquery_config(subquery_config);
             let pk_path = data_path.join(PathBuf::from("pk.bin"));
             let mut f = File::open(pk_path).unwrap();
-            let pk = ProvingKey::<G1Affine>::read::<_, AxiomCircuit<Fr, Http, AxiomCompute<A>>>(
-                &mut f,
-                SerdeFormat::RawBytes,
-                pinning.params,
-            )
+            let pk = ProvingKey::<G1Affine>::read::<
+                _,
+                AxiomCircuit<Fr, SharedDataSource, AxiomCompute<A>>,
+            >(&mut f, SerdeFormat::RawBytes, pinning.params)
             .unwrap();
-            compute.use_inputs(input).prove(pk);
+            compute
+                .use_inputs(input)
+                .prove(pk)
+                .unwrap_or_else(|e| panic!("{e}"));
         }
         SnarkCmd::Run => {
             let pinning_path = data_path.join(PathBuf::from("pinning.json"));
@@ -240,16 +662,21 @@ where
             let pinning: AxiomCircuitPinning = serde_json::from_reader(f).unwrap();
             let compute = AxiomCompute::<A>::new()
                 .use_pinning(pinning.clone())
-                .use_provider(provider);
+                .use_data_source(data_source)
+                .use_subquery_config(subquery_config);
             let pk_path = data_path.join(PathBuf::from("pk.bin"));
             let mut f = File::open(pk_path).unwrap();
-            let pk = ProvingKey::<G1Affine>::read::<_, AxiomCircuit<Fr, Http, AxiomCompute<A>>>(
-                &mut f,
-                SerdeFormat::RawBytes,
-                pinning.params,
-            )
+            let pk = ProvingKey::<G1Affine>::read::<
+                _,
+                AxiomCircuit<Fr, SharedDataSource, AxiomCompute<A>>,
+            >(&mut f, SerdeFormat::RawBytes, pinning.params)
             .unwrap();
-            let output = compute.use_inputs(input).run(pk);
+            let output = compute
+                .use_inputs(input)
+                .run(pk)
+                .unwrap_or_else(|e| panic!("{e}"));
+            let compute_output = decode_compute_output::<A>(output.data.compute_results())
+                .unwrap_or_else(|e| panic!("{e:#}"));
             let output_path = data_path.join(PathBuf::from("output.snark"));
             let f = File::create(&output_path)
                 .unwrap_or_else(|_| panic!("Could not create file at {output_path:?}"));
@@ -260,7 +687,18 @@ where
             }
             let f = File::create(&output_json_path)
                 .unwrap_or_else(|_| panic!("Could not create file at {output_json_path:?}"));
-            serde_json::to_writer_pretty(&f, &output.data).expect("Writing output should not fail");
+            let query = source_chain_id.map(|source_chain_id| {
+                build_axiom_v2_query_data(source_chain_id, &output.data, &output.compute_query)
+                    .expect("Computing the query hash should not fail")
+            });
+            if query.is_none() {
+                warn!(
+                    "No `source_chain_id` given, so the query hash is not written to output.json"
+                );
+            }
+            let run_output = AxiomV2RunOutput::new(output, query, compute_output);
+            serde_json::to_writer_pretty(&f, &run_output).expect("Writing output should not fail");
         }
+        SnarkCmd::SendQuery(_) | SnarkCmd::Solidity(_) => unreachable!(),
     }
 }
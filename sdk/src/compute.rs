@@ -1,25 +1,36 @@
 use std::{
     fmt::Debug,
+    ops::RangeInclusive,
     sync::{Arc, Mutex},
 };
 
+use anyhow::{bail, Result};
 use axiom_circuit::{
     axiom_eth::{
-        halo2_base::{gates::RangeChip, AssignedValue},
-        halo2_proofs::plonk::{ProvingKey, VerifyingKey},
+        halo2_base::{gates::RangeChip, AssignedValue, Context},
+        halo2_proofs::plonk::{Circuit, ProvingKey, VerifyingKey},
         halo2curves::bn256::G1Affine,
         rlc::circuit::builder::RlcCircuitBuilder,
         snark_verifier_sdk::Snark,
         utils::hilo::HiLo,
     },
     input::flatten::InputFlatten,
+    params::{ParamsObjective, ParamsSearch},
     run::inner::{keygen, mock, prove, run},
     scaffold::{AxiomCircuit, AxiomCircuitScaffold},
-    subquery::caller::SubqueryCaller,
+    solidity::{SolidityCallbackStub, SolidityResult},
+    stats::CircuitStats,
+    subquery::{
+        caller::{SubqueryCaller, SubqueryConfig},
+        source::SharedDataSource,
+    },
     types::{AxiomCircuitParams, AxiomCircuitPinning, AxiomV2CircuitOutput},
-    utils::to_hi_lo,
+    utils::{get_query_schema_from_vk, to_hi_lo},
+};
+use ethers::{
+    providers::{Http, Provider},
+    types::H256,
 };
-use ethers::providers::{Http, Provider};
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{api::AxiomAPI, Fr};
@@ -30,24 +41,79 @@ pub trait AxiomComputeInput: Clone + Default + Debug {
     type LogicInput: Clone + Debug + Serialize + DeserializeOwned + Into<Self::Input<Fr>>;
     /// The type of the circuit input to the compute function
     type Input<T: Copy>: Clone + InputFlatten<T>;
+
+    /// Constrains the assigned inputs before the compute function is run, as declared with the
+    /// field attributes of `#[AxiomComputeInput]` (ex: `#[range(bits = 64)]`)
+    #[allow(unused_variables)]
+    fn constrain_inputs(
+        ctx: &mut Context<Fr>,
+        range: &RangeChip<Fr>,
+        assigned_inputs: &Self::Input<AssignedValue<Fr>>,
+    ) {
+    }
+
+    /// The number of field elements of the fields marked `#[public]`
+    const NUM_PUBLIC_INPUTS: usize = 0;
+
+    /// Returns the assigned fields marked `#[public]`, flattened, which are exposed as public
+    /// instances before the compute function is run
+    #[allow(unused_variables)]
+    fn public_inputs(assigned_inputs: &Self::Input<AssignedValue<Fr>>) -> Vec<AssignedValue<Fr>> {
+        vec![]
+    }
+}
+
+/// A trait for specifying the typed results of an Axiom Compute function
+pub trait AxiomComputeOutput: Sized + Serialize {
+    /// The type of the circuit output of the compute function
+    type CircuitOutput;
+    /// Constrains the circuit output and returns its results, in the order of its fields
+    fn results(api: &mut AxiomAPI, output: Self::CircuitOutput) -> Vec<AxiomResult>;
+    /// Decodes the native output from the `compute_results` of a run
+    fn decode(compute_results: &[H256]) -> Result<Self>;
+    /// Returns the name and Solidity type of each result, in order
+    fn solidity_results() -> Vec<SolidityResult>;
+}
+
+/// The output of a compute function that returns untyped results
+impl AxiomComputeOutput for () {
+    type CircuitOutput = Vec<AxiomResult>;
+    fn results(_api: &mut AxiomAPI, output: Self::CircuitOutput) -> Vec<AxiomResult> {
+        output
+    }
+    fn decode(_compute_results: &[H256]) -> Result<Self> {
+        Ok(())
+    }
+    fn solidity_results() -> Vec<SolidityResult> {
+        vec![]
+    }
 }
 
 /// A trait for specifying an Axiom Compute function
 pub trait AxiomComputeFn: AxiomComputeInput {
     /// An optional type for the first phase payload -- only needed if you are using `compute_phase1`
     type FirstPhasePayload: Clone + Default = ();
+    /// An optional type for the typed results of the compute function, derived with `#[AxiomComputeOutput]`
+    type Output: AxiomComputeOutput = ();
+    /// The number of values that can be exposed publicly, with `#[public]` input fields or
+    /// `AxiomAPI::expose_public`. A circuit with public values can only be verified off-chain.
+    const MAX_PUBLIC_INSTANCES: usize = Self::NUM_PUBLIC_INPUTS;
 
-    /// Axiom Compute function
+    /// Axiom Compute function, which returns the circuit output of `Output`, or a
+    /// `Vec<AxiomResult>` if it is not set
     fn compute(
         api: &mut AxiomAPI,
         assigned_inputs: Self::Input<AssignedValue<Fr>>,
-    ) -> Vec<AxiomResult>;
+    ) -> <Self::Output as AxiomComputeOutput>::CircuitOutput;
 
     /// An optional function that overrides `compute` to specify phase0 circuit logic for circuits that require a challenge
     fn compute_phase0(
         api: &mut AxiomAPI,
         assigned_inputs: Self::Input<AssignedValue<Fr>>,
-    ) -> (Vec<AxiomResult>, Self::FirstPhasePayload) {
+    ) -> (
+        <Self::Output as AxiomComputeOutput>::CircuitOutput,
+        Self::FirstPhasePayload,
+    ) {
         (Self::compute(api, assigned_inputs), Default::default())
     }
 
@@ -66,24 +132,26 @@ pub trait AxiomComputeFn: AxiomComputeInput {
 #[derive(Debug, Clone)]
 /// Helper struct that contains all the necessary metadata and inputs to run an Axiom Compute function
 pub struct AxiomCompute<A: AxiomComputeFn> {
-    provider: Option<Provider<Http>>,
+    data_source: Option<SharedDataSource>,
     params: Option<AxiomCircuitParams>,
     pinning: Option<AxiomCircuitPinning>,
     input: Option<A::LogicInput>,
+    subquery_config: SubqueryConfig,
 }
 
 impl<A: AxiomComputeFn> Default for AxiomCompute<A> {
     fn default() -> Self {
         Self {
-            provider: None,
+            data_source: None,
             params: None,
             input: None,
             pinning: None,
+            subquery_config: Default::default(),
         }
     }
 }
 
-impl<A: AxiomComputeFn> AxiomCircuitScaffold<Http, Fr> for AxiomCompute<A>
+impl<A: AxiomComputeFn> AxiomCircuitScaffold<SharedDataSource, Fr> for AxiomCompute<A>
 where
     A::Input<Fr>: Default + Debug,
     A::Input<AssignedValue<Fr>>: Debug,
@@ -91,21 +159,29 @@ where
     type InputValue = A::Input<Fr>;
     type InputWitness = A::Input<AssignedValue<Fr>>;
     type FirstPhasePayload = A::FirstPhasePayload;
+    const MAX_PUBLIC_INSTANCES: usize = A::MAX_PUBLIC_INSTANCES;
 
     fn virtual_assign_phase0(
         builder: &mut RlcCircuitBuilder<Fr>,
         range: &RangeChip<Fr>,
-        subquery_caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+        subquery_caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
         callback: &mut Vec<HiLo<AssignedValue<Fr>>>,
         assigned_inputs: Self::InputWitness,
     ) -> <A as AxiomComputeFn>::FirstPhasePayload {
         let mut api = AxiomAPI::new(builder, range, subquery_caller);
-        let (result, payload) = A::compute_phase0(&mut api, assigned_inputs);
-        let hilo_output = result
+        A::constrain_inputs(api.ctx(), range, &assigned_inputs);
+        for value in A::public_inputs(&assigned_inputs) {
+            api.expose_public(value);
+        }
+        let (output, payload) = A::compute_phase0(&mut api, assigned_inputs);
+        let hilo_output = A::Output::results(&mut api, output)
             .into_iter()
-            .map(|result| match result {
-                AxiomResult::HiLo(hilo) => hilo,
-                AxiomResult::AssignedValue(val) => to_hi_lo(api.ctx(), range, val),
+            .map(|result| {
+                api.subquery_caller().lock().unwrap().add_output();
+                match result {
+                    AxiomResult::HiLo(hilo) => hilo,
+                    AxiomResult::AssignedValue(val) => to_hi_lo(api.ctx(), range, val),
+                }
             })
             .collect::<Vec<_>>();
         callback.extend(hilo_output);
@@ -133,7 +209,12 @@ where
 
     /// Set the provider for the AxiomCompute instance
     pub fn set_provider(&mut self, provider: Provider<Http>) {
-        self.provider = Some(provider);
+        self.set_data_source(Arc::new(provider));
+    }
+
+    /// Set the subquery data source for the AxiomCompute instance
+    pub fn set_data_source(&mut self, data_source: SharedDataSource) {
+        self.data_source = Some(data_source);
     }
 
     /// Set the params for the AxiomCompute instance
@@ -151,12 +232,23 @@ where
         self.pinning = Some(pinning);
     }
 
+    /// Set the subquery config for the AxiomCompute instance
+    pub fn set_subquery_config(&mut self, subquery_config: SubqueryConfig) {
+        self.subquery_config = subquery_config;
+    }
+
     /// Use the given provider for the AxiomCompute instance
     pub fn use_provider(mut self, provider: Provider<Http>) -> Self {
         self.set_provider(provider);
         self
     }
 
+    /// Use the given subquery data source for the AxiomCompute instance
+    pub fn use_data_source(mut self, data_source: SharedDataSource) -> Self {
+        self.set_data_source(data_source);
+        self
+    }
+
     /// Use the given params for the AxiomCompute instance
     pub fn use_params(mut self, params: AxiomCircuitParams) -> Self {
         self.set_params(params);
@@ -175,26 +267,39 @@ where
         self
     }
 
+    /// Use the given subquery config for the AxiomCompute instance
+    pub fn use_subquery_config(mut self, subquery_config: SubqueryConfig) -> Self {
+        self.set_subquery_config(subquery_config);
+        self
+    }
+
     /// Check that all the necessary configurations are set
     fn check_all_set(&self) {
-        assert!(self.provider.is_some());
+        assert!(self.data_source.is_some());
         assert!(self.pinning.is_some());
         assert!(self.input.is_some());
     }
 
-    /// Check that the provider and params are set
-    fn check_provider_and_params_set(&self) {
-        assert!(self.provider.is_some());
+    /// Check that the data source and params are set
+    fn check_data_source_and_params_set(&self) {
+        assert!(self.data_source.is_some());
         assert!(self.params.is_some());
     }
 
     /// Run the mock prover
-    pub fn mock(&self) {
-        self.check_provider_and_params_set();
-        let provider = self.provider.clone().unwrap();
+    ///
+    /// Returns an error listing the subqueries that could not be fetched, if any
+    pub fn mock(&self) -> Result<()> {
+        self.check_data_source_and_params_set();
+        let data_source = self.data_source.clone().unwrap();
         let params = self.params.clone().unwrap();
         let converted_input = self.input.clone().map(|input| input.into());
-        mock::<Http, Self>(provider, params, converted_input);
+        mock::<SharedDataSource, Self>(
+            data_source,
+            params,
+            converted_input,
+            self.subquery_config.clone(),
+        )
     }
 
     /// Run key generation and return the proving and verifying keys, and the circuit pinning
@@ -205,34 +310,106 @@ where
         ProvingKey<G1Affine>,
         AxiomCircuitPinning,
     ) {
-        self.check_provider_and_params_set();
-        let provider = self.provider.clone().unwrap();
+        self.check_data_source_and_params_set();
+        let data_source = self.data_source.clone().unwrap();
         let params = self.params.clone().unwrap();
-        keygen::<Http, Self>(provider, params, None)
+        keygen::<SharedDataSource, Self>(data_source, params, None, self.subquery_config.clone())
     }
 
     /// Run the prover and return the resulting snark
-    pub fn prove(&self, pk: ProvingKey<G1Affine>) -> Snark {
+    ///
+    /// Returns an error listing the subqueries that could not be fetched, if any
+    pub fn prove(&self, pk: ProvingKey<G1Affine>) -> Result<Snark> {
         self.check_all_set();
-        let provider = self.provider.clone().unwrap();
+        let data_source = self.data_source.clone().unwrap();
         let converted_input = self.input.clone().map(|input| input.into());
-        prove::<Http, Self>(provider, self.pinning.clone().unwrap(), converted_input, pk)
+        prove::<SharedDataSource, Self>(
+            data_source,
+            self.pinning.clone().unwrap(),
+            converted_input,
+            self.subquery_config.clone(),
+            pk,
+        )
     }
 
     /// Run the prover and return the outputs needed to make an on-chain compute query
-    pub fn run(&self, pk: ProvingKey<G1Affine>) -> AxiomV2CircuitOutput {
+    ///
+    /// Returns an error listing the subqueries that could not be fetched, if any
+    pub fn run(&self, pk: ProvingKey<G1Affine>) -> Result<AxiomV2CircuitOutput> {
         self.check_all_set();
-        let provider = self.provider.clone().unwrap();
+        let data_source = self.data_source.clone().unwrap();
+        let converted_input = self.input.clone().map(|input| input.into());
+        run::<SharedDataSource, Self>(
+            data_source,
+            self.pinning.clone().unwrap(),
+            converted_input,
+            self.subquery_config.clone(),
+            pk,
+        )
+    }
+
+    /// Returns a Solidity `AxiomV2Client` named `contract_name` that accepts the queries of the
+    /// circuit with verifying key `vk` and decodes the results of `A::Output`
+    ///
+    /// The pinning written by `keygen` must be set
+    pub fn solidity_callback(
+        &self,
+        contract_name: &str,
+        vk: &VerifyingKey<G1Affine>,
+    ) -> Result<String> {
+        assert!(self.pinning.is_some());
+        let results = A::Output::solidity_results();
+        if results.is_empty() {
+            bail!("The compute function has no typed results, declare them with #[AxiomComputeOutput]");
+        }
+        let query_schema =
+            get_query_schema_from_vk(vk, self.pinning.as_ref().unwrap(), results.len())?;
+        SolidityCallbackStub::new(contract_name, query_schema)
+            .use_results(results)
+            .generate()
+    }
+
+    /// Returns the params of degree `k` fitted to a witness generation pass of the compute function
+    /// on the inputs, if set, keeping the circuit type of the params that are set
+    ///
+    /// See [AxiomCircuit::auto_configure]
+    pub fn auto_params(&self, k: usize) -> AxiomCircuitParams {
+        let converted_input = self.input.clone().map(|input| input.into());
+        let mut circuit = self.circuit().use_inputs(converted_input);
+        circuit.auto_configure(k);
+        circuit.params()
+    }
+
+    /// Fits the compute function to each degree in `ks` and picks the feasible degree that is best
+    /// for `objective`, on the inputs, if set
+    ///
+    /// See [AxiomCircuit::search_params]
+    pub fn search_params(
+        &self,
+        ks: RangeInclusive<usize>,
+        objective: ParamsObjective,
+    ) -> ParamsSearch {
+        let converted_input = self.input.clone().map(|input| input.into());
+        let mut circuit = self.circuit().use_inputs(converted_input);
+        circuit.search_params(ks, objective)
+    }
+
+    /// Returns the cells, constraints, keccak_f permutations, subqueries and outputs used by the
+    /// compute function on the inputs, if set, and the cells used by each region named with
+    /// [AxiomAPI::region]
+    ///
+    /// See [AxiomCircuit::stats]
+    pub fn stats(&self) -> CircuitStats {
         let converted_input = self.input.clone().map(|input| input.into());
-        run::<Http, Self>(provider, self.pinning.clone().unwrap(), converted_input, pk)
+        self.circuit().use_inputs(converted_input).stats()
     }
 
     /// Returns an [AxiomCircuit] instance, for functions that expect the halo2 circuit trait
-    pub fn circuit(&self) -> AxiomCircuit<Fr, Http, Self> {
-        self.check_provider_and_params_set();
-        let provider = self.provider.clone().unwrap();
+    pub fn circuit(&self) -> AxiomCircuit<Fr, SharedDataSource, Self> {
+        self.check_data_source_and_params_set();
+        let data_source = self.data_source.clone().unwrap();
         let params = self.params.clone().unwrap();
-        AxiomCircuit::new(provider, params)
+        AxiomCircuit::new(data_source, params).use_subquery_config(self.subquery_config.clone())
     }
 }
 
@@ -0,0 +1,126 @@
+//! Checks that the derive macros reject the inputs they do not support.
+//!
+//! Each of the failing examples is a small change to this one, which compiles:
+//!
+//! ```
+//! use axiom_sdk::axiom::{AxiomComputeInput, AxiomInput};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(AxiomInput, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+//! pub enum Side {
+//!     #[default]
+//!     Buy,
+//!     Sell,
+//! }
+//!
+//! #[AxiomComputeInput]
+//! pub struct ExampleInput {
+//!     #[range(bits = 8)]
+//!     pub fee: u64,
+//!     pub side: Side,
+//! }
+//! ```
+//!
+//! The input struct must be named `_Input` unless the circuit struct is named:
+//!
+//! ```compile_fail
+//! use axiom_sdk::axiom::AxiomComputeInput;
+//!
+//! #[AxiomComputeInput]
+//! pub struct Example {
+//!     pub fee: u64,
+//! }
+//! ```
+//!
+//! `circuit = Name` is the only argument:
+//!
+//! ```compile_fail
+//! use axiom_sdk::axiom::AxiomComputeInput;
+//!
+//! #[AxiomComputeInput(name = ExampleAssigned)]
+//! pub struct ExampleInput {
+//!     pub fee: u64,
+//! }
+//! ```
+//!
+//! The fields must be named:
+//!
+//! ```compile_fail
+//! use axiom_sdk::axiom::AxiomComputeInput;
+//!
+//! #[AxiomComputeInput]
+//! pub struct ExampleInput(u64);
+//! ```
+//!
+//! `#[range]` takes the number of bits:
+//!
+//! ```compile_fail
+//! use axiom_sdk::axiom::AxiomComputeInput;
+//!
+//! #[AxiomComputeInput]
+//! pub struct ExampleInput {
+//!     #[range(max = 8)]
+//!     pub fee: u64,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! use axiom_sdk::axiom::AxiomComputeInput;
+//!
+//! #[AxiomComputeInput]
+//! pub struct ExampleInput {
+//!     #[range()]
+//!     pub fee: u64,
+//! }
+//! ```
+//!
+//! `#[max]` takes a value:
+//!
+//! ```compile_fail
+//! use axiom_sdk::axiom::AxiomComputeInput;
+//!
+//! #[AxiomComputeInput]
+//! pub struct ExampleInput {
+//!     #[max]
+//!     pub fee: u64,
+//! }
+//! ```
+//!
+//! A nested struct takes the same arguments:
+//!
+//! ```compile_fail
+//! use axiom_sdk::axiom::AxiomInput;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(AxiomInput, Clone, Debug, Default, Serialize, Deserialize)]
+//! #[axiom_input(name = ExampleAssigned)]
+//! pub struct Order {
+//!     pub fee: u64,
+//! }
+//! ```
+//!
+//! A nested enum cannot have fields or generics:
+//!
+//! ```compile_fail
+//! use axiom_sdk::axiom::AxiomInput;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(AxiomInput, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+//! pub enum Side {
+//!     #[default]
+//!     Buy,
+//!     Sell(u64),
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! use axiom_sdk::axiom::AxiomInput;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(AxiomInput, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+//! pub enum Side<const N: usize> {
+//!     #[default]
+//!     Buy,
+//!     Sell,
+//! }
+//! ```
@@ -22,6 +22,14 @@
 //! This attribute will also generate a new struct with `Input` replaced with `CircuitInput` (ie. `AccountAgeInput` -> `AccountAgeCircuitInput`), which has all the fields of the specified struct,
 //! but with `halo2-lib` types to be used inside your circuit (like `AssignedValue<Fr>`).
 //!
+//! Inputs of variable length are declared with a maximum length: `VarLenVec<T, MAX>`, `VarLenBytes<MAX>` and `VarLenString<MAX>`. In the circuit they are padded to `MAX` elements and come with an assigned `len`, and `VarLenVecInput` has helpers such as `mask_values` and `sum` that ignore the padding.
+//!
+//! Structs and fieldless enums can be nested in your input with `#[derive(AxiomInput)]`. A struct gets its own circuit struct (named like the `CircuitInput` struct above, or with `#[axiom_input(circuit = MyAssignedStruct)]`), and an enum is assigned as its discriminant. The generated circuit struct of your input can also be named freely with `#[AxiomComputeInput(circuit = MyAssignedInput)]`, in which case the input struct can have any name.
+//!
+//! Inputs are not constrained by default, so a `u64` input can hold any field element. Fields can be constrained before `compute` runs with `#[range(bits = 64)]` (a range check) or `#[max(1000)]` (an inclusive upper bound), and `Address` inputs are always range checked to 160 bits.
+//!
+//! Inputs are private by default. A field marked `#[public]` is exposed as a public instance of the circuit, and other values can be exposed from `compute` with `api.expose_public(value)` once `AxiomComputeFn::MAX_PUBLIC_INSTANCES` is raised to make room for them. Public values are placed in a second instance column, padded with zeros to `MAX_PUBLIC_INSTANCES`, so the first column keeps the compute results and subquery results in the layout the AxiomV2Query contract expects. This layout is recorded as `instance_layout` in the pinning. They are meant for verifiers outside of Axiom, as the AxiomV2Query contract verifies circuits with a single instance column: the compute query of such a circuit has no vkey or proof, and `sendQuery` calldata cannot be built for it.
+//!
 //! Here is an example:
 //!
 //! ```ignore
@@ -77,6 +85,35 @@
 //! }
 //! ```
 //!
+//! ### Typed Outputs
+//!
+//! Instead of an untyped `Vec<AxiomResult>`, the results can be described by a struct annotated with the `#[AxiomComputeOutput]` attribute. The struct must be named `_Output`, and its fields must implement the `RawOutput` trait (`bool`, unsigned integers, `Address`, `U256` and `H256`). This generates a struct with `Output` replaced with `CircuitOutput`, which `compute` returns. Each of its fields is range checked and returned as a result, in order:
+//!
+//! ```ignore
+//! #[AxiomComputeOutput]
+//! pub struct AccountAgeOutput {
+//!     pub addr: Address,
+//!     pub claimed_block_number: u64,
+//! }
+//!
+//! impl AxiomComputeFn for AccountAgeInput {
+//!     type Output = AccountAgeOutput;
+//!
+//!     fn compute(
+//!         api: &mut AxiomAPI,
+//!         assigned_inputs: AccountAgeCircuitInput<AssignedValue<Fr>>,
+//!     ) -> AccountAgeCircuitOutput {
+//!         // ...
+//!         AccountAgeCircuitOutput {
+//!             addr: assigned_inputs.addr,
+//!             claimed_block_number: assigned_inputs.claimed_block_number,
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! `AccountAgeOutput::decode` decodes the `compute_results` of a run back into the struct, and the `run` command writes them to `output.json` under `computeOutput`.
+//!
 //! ### Running The Circuit
 //!
 //! To run your circuit, create a `main` function call the `run_cli` function with your input struct as the generic parameter:
@@ -90,11 +127,15 @@
 //!
 //! ```ignore
 //! Commands:
-//!     mock    Run the mock prover
-//!     keygen  Generate new proving & verifying keys
-//!     prove   Generate a new proof
-//!     run     Generate an Axiom compute query
-//!     help    Print this message or the help of the given subcommand(s)
+//!     mock           Run the mock prover
+//!     keygen         Generate new proving & verifying keys
+//!     prove          Generate a new proof
+//!     run            Generate an Axiom compute query
+//!     send-query     Generate a `sendQuery` transaction for the output of `run`
+//!     search-params  Fit the circuit to a range of degrees and recommend one
+//!     stats          Report the cells, constraints and subqueries used by the circuit
+//!     solidity       Write a Solidity callback contract for the circuit of `keygen`
+//!     help           Print this message or the help of the given subcommand(s)
 //!
 //! Options:
 //!     -k, --degree <DEGREE>        To determine the size of your circuit (12..25)
@@ -102,6 +143,14 @@
 //!     -i, --input <INPUT_PATH>     JSON inputs to feed into your circuit
 //!     -d, --data-path <DATA_PATH>  For saving build artifacts (optional)
 //!     -c, --config <CONFIG>        For custom advanced usage only (optional)
+//!         --auto-config            Fit the circuit columns and lookup bits to the degree with witness generation passes, for mock and keygen (optional)
+//!         --fixture <FIXTURE>      JSON subquery fixture to use instead of a JSON RPC provider (optional)
+//!         --source-chain-id <CHAIN_ID> Chain ID the query is made on, if not that of the JSON RPC provider (optional)
+//!         --subquery-cache <MODE>  Whether to use, refresh or bypass the subquery cache under the data path [default: use]
+//!         --prefetch <PARALLELISM> Fetch all subqueries before witness generation with up to this many concurrent requests (optional)
+//!         --verify-proofs          Verify subquery results against the roots of their block before proving
+//!         --mock-seed <SEED>       Answer subqueries with pseudo-random values from this seed during keygen (optional)
+//!         --mock-fixture <FIXTURE> JSON subquery fixture of a sample run to answer subqueries with during keygen (optional)
 //!     -h, --help                   Print help
 //!     -V, --version                Print version
 //! ```
@@ -113,6 +162,12 @@
 //! ```
 //!
 //! where `PROVIDER_URI` is a JSON-RPC URI, and `CMD` is `mock`, `prove`, `keygen`, or `run`.
+//!
+//! With `--auto-config`, `mock` and `keygen` run witness generation to size the circuit for the given `-k` (advice, lookup advice, fixed and RLC columns, and lookup bits as wide as the widest range check, up to `k - 1`), so `keygen` writes a ready `pinning.json` without a config file. The circuit type is taken from `--config`, if given, and RLC columns are only fitted for RLC and keccak circuits. Other commands reject `--auto-config`.
+//!
+//! To find a degree, `search-params` runs this for each `k` from `--min-k` to `--max-k` (default 10 to 20) and prints a table of the fitted columns and estimated proving cost (columns × 2^k, counting the columns of the keccak sub-circuit). It recommends the smallest degree the circuit fits in, or the cheapest one to prove with `--objective min-cost`.
+//!
+//! The `stats` command runs witness generation on the inputs at the degree given with `-k` and prints the advice, lookup and RLC cells, fixed constants, copy constraints, keccak_f permutations, subqueries by type and outputs of your circuit. To see where the cells go, wrap parts of your compute function in named regions with `api.region("name", |api| { ... })`, which are listed with the cells they use.
 
 #![allow(incomplete_features)]
 #![feature(associated_type_defaults)]
@@ -127,18 +182,40 @@ pub use axiom_circuit::{
 
 /// The types and traits required to implement an Axiom Compute function
 pub mod axiom {
-    pub use axiom_sdk_derive::AxiomComputeInput;
+    pub use axiom_sdk_derive::{AxiomComputeInput, AxiomComputeOutput, AxiomInput};
 
     pub use crate::{
         api::AxiomAPI,
-        compute::{AxiomCompute, AxiomComputeFn, AxiomComputeInput, AxiomResult},
+        compute::{
+            AxiomCompute, AxiomComputeFn, AxiomComputeInput, AxiomComputeOutput, AxiomResult,
+        },
+    };
+}
+/// Encodings and hashes of a query as the AxiomV2Query contract expects them
+pub mod query {
+    pub use axiom_circuit::{
+        query::{
+            build_send_query_calldata, build_send_query_tx, encode_send_query_calldata,
+            AxiomV2Callback, AxiomV2FeeData, SendQueryArgs, DEFAULT_CALLBACK_GAS_LIMIT,
+            DEFAULT_MAX_FEE_PER_GAS, SEND_QUERY_SIGNATURE,
+        },
+        types::{AxiomV2DataAndResults, AxiomV2QueryData, AxiomV2RunOutput},
+        utils::{
+            build_axiom_v2_data_query, build_axiom_v2_query_data, build_offchain_compute_query,
+            encode_data_query, get_data_query_hash, get_query_hash, get_query_schema,
+            get_query_schema_from_vk,
+        },
     };
 }
 /// Contains a CLI for running any Axiom Compute function (any struct that implements the `AxiomComputeFn` trait)
 pub mod cmd;
 /// Contains the traits and types required to implement an Axiom Compute function (re-exported from the `axiom` module)
 pub(crate) mod compute;
+#[cfg(doctest)]
+mod derive_tests;
 /// Module with all subquery types and builders
 pub mod subquery;
+/// Generates Solidity callback contracts that decode compute results
+pub use axiom_circuit::solidity;
 /// Re-export ethers-rs
 pub use ethers;
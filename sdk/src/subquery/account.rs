@@ -3,9 +3,11 @@ use std::sync::{Arc, Mutex};
 use axiom_circuit::{
     axiom_codec::HiLo,
     axiom_eth::halo2_base::{AssignedValue, Context},
-    subquery::{caller::SubqueryCaller, types::AssignedAccountSubquery, AccountField},
+    subquery::{
+        caller::SubqueryCaller, source::SharedDataSource, types::AssignedAccountSubquery,
+        AccountField,
+    },
 };
-use ethers::providers::Http;
 
 use crate::Fr;
 
@@ -14,12 +16,12 @@ pub struct Account<'a> {
     pub block_number: AssignedValue<Fr>,
     pub addr: AssignedValue<Fr>,
     ctx: &'a mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
 }
 
 pub(crate) fn get_account(
     ctx: &mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
     block_number: AssignedValue<Fr>,
     addr: AssignedValue<Fr>,
 ) -> Account {
@@ -3,9 +3,11 @@ use std::sync::{Arc, Mutex};
 use axiom_circuit::{
     axiom_codec::{special_values::HEADER_LOGS_BLOOM_FIELD_IDX_OFFSET, HiLo},
     axiom_eth::halo2_base::{AssignedValue, Context},
-    subquery::{caller::SubqueryCaller, types::AssignedHeaderSubquery, HeaderField},
+    subquery::{
+        caller::SubqueryCaller, source::SharedDataSource, types::AssignedHeaderSubquery,
+        HeaderField,
+    },
 };
-use ethers::providers::Http;
 
 use crate::Fr;
 
@@ -13,12 +15,12 @@ use crate::Fr;
 pub struct Header<'a> {
     pub block_number: AssignedValue<Fr>,
     ctx: &'a mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
 }
 
 pub(crate) fn get_header(
     ctx: &mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
     block_number: AssignedValue<Fr>,
 ) -> Header {
     Header {
@@ -32,6 +34,9 @@ impl<'a> Header<'a> {
     /// Fetches the header subquery and returns the HiLo<AssignedValue<Fr>> result
     ///
     /// * `field` - The header field to fetch
+    ///
+    /// Fields added by a fork (ie. `BaseFeePerGas` or `BlobGasUsed`) are only available for
+    /// blocks after that fork.
     pub fn call(self, field: HeaderField) -> HiLo<AssignedValue<Fr>> {
         let field_constant = self.ctx.load_constant(Fr::from(field));
         let mut subquery_caller = self.caller.lock().unwrap();
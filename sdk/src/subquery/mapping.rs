@@ -3,9 +3,11 @@ use std::sync::{Arc, Mutex};
 use axiom_circuit::{
     axiom_codec::{constants::MAX_SOLIDITY_MAPPING_KEYS, HiLo},
     axiom_eth::halo2_base::{AssignedValue, Context},
-    subquery::{caller::SubqueryCaller, types::AssignedSolidityNestedMappingSubquery},
+    subquery::{
+        caller::SubqueryCaller, source::SharedDataSource,
+        types::AssignedSolidityNestedMappingSubquery,
+    },
 };
-use ethers::providers::Http;
 
 use crate::Fr;
 
@@ -15,12 +17,12 @@ pub struct SolidityMapping<'a> {
     pub addr: AssignedValue<Fr>,
     pub mapping_slot: HiLo<AssignedValue<Fr>>,
     ctx: &'a mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
 }
 
 pub(crate) fn get_mapping(
     ctx: &mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
     block_number: AssignedValue<Fr>,
     addr: AssignedValue<Fr>,
     mapping_slot: HiLo<AssignedValue<Fr>>,
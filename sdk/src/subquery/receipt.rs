@@ -15,9 +15,12 @@ use axiom_circuit::{
         },
         utils::encode_h256_to_hilo,
     },
-    subquery::{caller::SubqueryCaller, types::AssignedReceiptSubquery, ReceiptField},
+    subquery::{
+        caller::SubqueryCaller, source::SharedDataSource, types::AssignedReceiptSubquery,
+        ReceiptField,
+    },
 };
-use ethers::{providers::Http, types::H256};
+use ethers::types::H256;
 
 use crate::Fr;
 
@@ -26,7 +29,7 @@ pub struct Receipt<'a> {
     pub block_number: AssignedValue<Fr>,
     pub tx_idx: AssignedValue<Fr>,
     ctx: &'a mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
 }
 
 /// Log subquery builder
@@ -35,12 +38,12 @@ pub struct Log<'a> {
     pub tx_idx: AssignedValue<Fr>,
     pub field_or_log_idx: AssignedValue<Fr>,
     ctx: &'a mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
 }
 
 pub(crate) fn get_receipt(
     ctx: &mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
     block_number: AssignedValue<Fr>,
     tx_idx: AssignedValue<Fr>,
 ) -> Receipt {
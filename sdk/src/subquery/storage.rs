@@ -3,9 +3,8 @@ use std::sync::{Arc, Mutex};
 use axiom_circuit::{
     axiom_codec::HiLo,
     axiom_eth::halo2_base::{AssignedValue, Context},
-    subquery::{caller::SubqueryCaller, types::AssignedStorageSubquery},
+    subquery::{caller::SubqueryCaller, source::SharedDataSource, types::AssignedStorageSubquery},
 };
-use ethers::providers::Http;
 
 use crate::Fr;
 
@@ -14,12 +13,12 @@ pub struct Storage<'a> {
     pub block_number: AssignedValue<Fr>,
     pub addr: AssignedValue<Fr>,
     ctx: &'a mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
 }
 
 pub(crate) fn get_storage(
     ctx: &mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
     block_number: AssignedValue<Fr>,
     addr: AssignedValue<Fr>,
 ) -> Storage {
@@ -9,9 +9,10 @@ use axiom_circuit::{
         gates::{GateChip, GateInstructions},
         AssignedValue, Context,
     },
-    subquery::{caller::SubqueryCaller, types::AssignedTxSubquery, TxField},
+    subquery::{
+        caller::SubqueryCaller, source::SharedDataSource, types::AssignedTxSubquery, TxField,
+    },
 };
-use ethers::providers::Http;
 
 use crate::Fr;
 
@@ -20,12 +21,12 @@ pub struct Tx<'a> {
     pub block_number: AssignedValue<Fr>,
     pub tx_idx: AssignedValue<Fr>,
     ctx: &'a mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
 }
 
 pub(crate) fn get_tx(
     ctx: &mut Context<Fr>,
-    caller: Arc<Mutex<SubqueryCaller<Http, Fr>>>,
+    caller: Arc<Mutex<SubqueryCaller<SharedDataSource, Fr>>>,
     block_number: AssignedValue<Fr>,
     tx_idx: AssignedValue<Fr>,
 ) -> Tx {
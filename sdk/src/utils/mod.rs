@@ -27,7 +27,8 @@ macro_rules! axiom_compute_tests {
                 .use_inputs($inputs())
                 .use_params(params())
                 .use_provider($crate::utils::provider())
-                .mock();
+                .mock()
+                .unwrap();
         }
 
         #[test]
@@ -44,7 +45,7 @@ macro_rules! axiom_compute_tests {
                 .use_params(params())
                 .use_provider($crate::utils::provider());
             let (_vk, pk) = compute.keygen();
-            compute.use_inputs($inputs()).prove(pk);
+            compute.use_inputs($inputs()).prove(pk).unwrap();
         }
 
         #[test]
@@ -53,7 +54,7 @@ macro_rules! axiom_compute_tests {
                 .use_params(params())
                 .use_provider($crate::utils::provider());
             let (_vk, pk) = compute.keygen();
-            compute.use_inputs($inputs()).run(pk);
+            compute.use_inputs($inputs()).run(pk).unwrap();
         }
     };
 }
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use axiom_circuit::{
+    axiom_eth::halo2_base::gates::circuit::BaseCircuitParams,
+    run::inner::mock,
+    subquery::source::{InMemoryDataSource, SharedDataSource},
+    types::AxiomCircuitParams,
+};
+use axiom_sdk::{
+    axiom::{AxiomAPI, AxiomCompute, AxiomComputeFn, AxiomComputeInput, AxiomInput, AxiomResult},
+    halo2_base::AssignedValue,
+    Fr,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(AxiomInput, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub enum Side {
+    #[default]
+    Buy = 1,
+    Sell = 3,
+}
+
+#[derive(AxiomInput, Clone, Debug, Default, Serialize, Deserialize)]
+#[axiom_input(circuit = AssignedOrder)]
+pub struct Order {
+    pub side: Side,
+    #[max(1000)]
+    pub amount: u64,
+}
+
+#[AxiomComputeInput(circuit = AssignedTrade)]
+pub struct Trade {
+    #[public]
+    pub id: u64,
+    #[range(bits = 8)]
+    pub fee: u64,
+    pub order: Order,
+}
+
+impl AxiomComputeFn for Trade {
+    fn compute(
+        _api: &mut AxiomAPI,
+        assigned_inputs: AssignedTrade<AssignedValue<Fr>>,
+    ) -> Vec<AxiomResult> {
+        vec![
+            assigned_inputs.fee.into(),
+            assigned_inputs.order.amount.into(),
+        ]
+    }
+}
+
+fn get_trade_test_params() -> AxiomCircuitParams {
+    AxiomCircuitParams::Base(BaseCircuitParams {
+        k: 12,
+        num_advice_per_phase: vec![4],
+        num_lookup_advice_per_phase: vec![1],
+        num_fixed: 1,
+        num_instance_columns: 1,
+        lookup_bits: Some(11),
+    })
+}
+
+fn get_trade(fee: u64, amount: u64) -> Trade {
+    Trade {
+        id: 7,
+        fee,
+        order: Order {
+            side: Side::Sell,
+            amount,
+        },
+    }
+}
+
+fn mock_trade_test(input: AssignedTrade<Fr>) {
+    let data_source: SharedDataSource = Arc::new(InMemoryDataSource::new());
+    mock::<_, AxiomCompute<Trade>>(
+        data_source,
+        get_trade_test_params(),
+        Some(input),
+        Default::default(),
+    )
+    .unwrap();
+}
+
+#[test]
+pub fn test_valid_trade() {
+    mock_trade_test(get_trade(255, 1000).into());
+}
+
+#[test]
+#[should_panic]
+pub fn test_range_attribute_overflow() {
+    mock_trade_test(get_trade(256, 1000).into());
+}
+
+#[test]
+#[should_panic]
+pub fn test_max_attribute_overflow() {
+    mock_trade_test(get_trade(255, 1001).into());
+}
+
+#[test]
+pub fn test_enum_discriminants() {
+    let input: AssignedTrade<Fr> = get_trade(0, 0).into();
+    assert_eq!(input.order.side, Fr::from(3));
+    let buy = Trade {
+        order: Order {
+            side: Side::Buy,
+            amount: 0,
+        },
+        ..get_trade(0, 0)
+    };
+    mock_trade_test(buy.into());
+}
+
+#[test]
+#[should_panic]
+pub fn test_enum_invalid_discriminant() {
+    let mut input: AssignedTrade<Fr> = get_trade(0, 0).into();
+    input.order.side = Fr::from(2);
+    mock_trade_test(input);
+}
+
+#[test]
+pub fn test_public_input() {
+    let data_source: SharedDataSource = Arc::new(InMemoryDataSource::new());
+    let runner = AxiomCompute::<Trade>::new()
+        .use_data_source(data_source)
+        .use_params(get_trade_test_params())
+        .circuit()
+        .use_inputs(Some(get_trade(0, 0).into()));
+    let layout = runner.instance_layout();
+    assert_eq!(layout.max_public_instances, 1);
+    assert_eq!(runner.instances()[1][layout.public()], [Fr::from(7)]);
+}